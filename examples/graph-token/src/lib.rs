@@ -1,4 +1,10 @@
-use subgraph::{address::Address, entity, eth, indexmap::indexmap, log, num::BigInt, store};
+use subgraph::{
+    address::Address,
+    entity::{self, Entity},
+    eth, log,
+    num::BigInt,
+    store,
+};
 
 /// `Transfer` event entry point.
 ///
@@ -24,23 +30,17 @@ fn transfer(event: eth::Event) {
     );
     let to = holder(event.parameters["to"].as_address().unwrap(), value.clone());
 
-    let id = event_id(&event);
-    let data = indexmap! {
-        "from".to_string()
-            => from,
-        "to".to_string()
-            => to,
-        "value".to_string()
-            => entity::Value::BigInt(value.clone()),
-        "blockNumber".to_string()
-            => entity::Value::BigInt(event.block.number),
-        "blockTimestamp".to_string()
-            => entity::Value::BigInt(event.block.timestamp),
-        "transactionHash".to_string()
-            => entity::Value::Bytes(event.transaction.hash.to_vec()),
-    };
-
-    store::set("Transfer", &id, &data);
+    let id = event.id();
+    let data = Entity::builder()
+        .field("from", from)
+        .field("to", to)
+        .field("value", value.clone())
+        .field("blockNumber", event.block.number)
+        .field("blockTimestamp", event.block.timestamp)
+        .field("transactionHash", event.transaction.hash)
+        .build();
+
+    store::set("Transfer", id, &data);
 }
 
 fn holder(address: Address, delta: BigInt) -> entity::Value {
@@ -48,26 +48,15 @@ fn holder(address: Address, delta: BigInt) -> entity::Value {
         return entity::Value::Null;
     }
 
-    let id = address.to_string();
-    let mut data = store::get("Holder", &id).unwrap_or_else(|| {
-        indexmap! {
-            "address".to_string()
-                => entity::Value::Bytes(address.0.to_vec()),
-            "balance".to_string()
-                => entity::Value::BigInt(BigInt::new(0)),
-        }
+    let mut data = store::get("Holder", address).unwrap_or_else(|| {
+        Entity::builder()
+            .field("address", address)
+            .field("balance", BigInt::new(0))
+            .build()
     });
-    data["balance"] = entity::Value::BigInt(data["balance"].as_big_int().unwrap().plus(&delta));
-
-    store::set("Holder", &id, &data);
-    entity::Value::String(id)
-}
+    let balance = data.get::<BigInt>("balance").unwrap();
+    data.set("balance", balance.plus(&delta));
 
-fn event_id(event: &eth::Event) -> String {
-    let bytes = eth::encode(&eth::Value::Tuple(vec![
-        eth::Value::FixedBytes(event.transaction.hash.to_vec()),
-        eth::Value::Uint(event.transaction_log_index.clone()),
-    ]))
-    .expect("failed to encode event ID");
-    subgraph::conv::hex(&bytes)
+    store::set("Holder", address, &data);
+    address.into()
 }
@@ -1,9 +1,9 @@
 //! 20-byte Ethereum addresses.
 
 use crate::{
-    conv,
+    conv, entity, eth,
     ffi::{
-        boxed::{AscBox, AscRef},
+        boxed::{AscBox, AscRef, HostRef},
         str::AscString,
         sys,
         types::AscAddress,
@@ -33,7 +33,8 @@ impl Address {
     /// Returns a new address from its string reprensentation.
     pub fn parse(s: impl AsRef<str>) -> Self {
         let str = AscString::new(s.as_ref());
-        let bytes = unsafe { &*sys::type_conversion__string_to_h160(str.as_ptr()) };
+        let bytes =
+            unsafe { HostRef::new(sys::type_conversion__string_to_h160(str.as_ptr())).into_ref() };
         Self::from_raw(bytes)
     }
 
@@ -71,3 +72,53 @@ impl FromStr for Address {
         Ok(Self::parse(s))
     }
 }
+
+impl From<Address> for entity::Value {
+    fn from(address: Address) -> Self {
+        Self::Bytes(address.0.to_vec())
+    }
+}
+
+impl TryFrom<entity::Value> for Address {
+    type Error = entity::ValueError;
+
+    fn try_from(value: entity::Value) -> Result<Self, Self::Error> {
+        match value {
+            entity::Value::Bytes(bytes) => {
+                let found = bytes.len();
+                let bytes = bytes.try_into().map_err(|_| entity::ValueError::Length {
+                    expected: 20,
+                    found,
+                })?;
+                Ok(Self(bytes))
+            }
+            value => Err(entity::ValueError::unexpected("bytes", value.kind())),
+        }
+    }
+}
+
+impl From<Address> for eth::Value {
+    fn from(address: Address) -> Self {
+        Self::Address(address)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        conv::serialize_fixed_bytes(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        conv::deserialize_fixed_bytes(deserializer).map(Self)
+    }
+}
@@ -0,0 +1,272 @@
+//! Bump allocator for Subgraph mappings.
+//!
+//! The Subgraph host instantiates a fresh WASM module for every trigger, so all
+//! memory is effectively reset between handler invocations. This allows using
+//! a simple bump allocator that never frees memory (except for the most recent
+//! allocation, which is common for temporary buffers), which is both smaller
+//! and faster than the default allocator.
+//!
+//! The amount of memory to reserve on the first allocation can be configured
+//! with the `SUBGRAPH_INITIAL_MEMORY` environment variable at build time, in
+//! bytes. Memory is grown on demand past the initial reservation.
+
+// NOTE: The allocator is only installed for WASM targets, where it can grow the
+// module's linear memory.
+#![cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    cell::Cell,
+    ptr,
+};
+
+/// The size of a WASM memory page.
+const PAGE_SIZE: usize = 0x10000;
+
+/// The number of bytes to reserve on the first allocation.
+const INITIAL_MEMORY: usize = match option_env!("SUBGRAPH_INITIAL_MEMORY") {
+    Some(value) => parse_usize(value),
+    None => 0,
+};
+
+#[cfg(target_arch = "wasm32")]
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator::new();
+
+/// A bump allocator over the WASM module's linear memory.
+struct BumpAllocator {
+    next: Cell<usize>,
+    end: Cell<usize>,
+}
+
+// SAFETY: The allocator is only installed for WASM modules, which the Subgraph
+// host runs on a single thread.
+unsafe impl Sync for BumpAllocator {}
+
+impl BumpAllocator {
+    const fn new() -> Self {
+        Self {
+            next: Cell::new(0),
+            end: Cell::new(0),
+        }
+    }
+
+    /// Initializes the heap bounds on the first allocation.
+    fn init(&self) {
+        let (start, end) = heap();
+        self.next.set(start);
+        self.end.set(end);
+        self.reserve(start.saturating_add(INITIAL_MEMORY));
+    }
+
+    /// Ensures that the heap extends to at least `end`, growing memory if
+    /// required. Returns `false` if memory could not be grown.
+    fn reserve(&self, end: usize) -> bool {
+        if end <= self.end.get() {
+            return true;
+        }
+        let Some(pages) = pages_to_grow(self.end.get(), end) else {
+            return false;
+        };
+        match grow(pages).and_then(|previous| memory_end(previous, pages)) {
+            Some(end) => {
+                self.end.set(end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the allocation is the most recent one.
+    fn is_last(&self, ptr: *mut u8, size: usize) -> bool {
+        ptr as usize + size == self.next.get()
+    }
+}
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.end.get() == 0 {
+            self.init();
+        }
+
+        let Some((start, end)) = bump(self.next.get(), layout) else {
+            return ptr::null_mut();
+        };
+        if !self.reserve(end) {
+            return ptr::null_mut();
+        }
+
+        self.next.set(end);
+        start as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if self.is_last(ptr, layout.size()) {
+            self.next.set(ptr as usize);
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // NOTE: Resize the most recent allocation in place, this makes growing
+        // buffers with repeated pushes cheap.
+        if self.is_last(ptr, layout.size()) {
+            let Some(end) = (ptr as usize).checked_add(new_size) else {
+                return ptr::null_mut();
+            };
+            if !self.reserve(end) {
+                return ptr::null_mut();
+            }
+            self.next.set(end);
+            return ptr;
+        }
+
+        // SAFETY: The caller guarantees that the new layout is valid.
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        }
+        new_ptr
+    }
+}
+
+/// Returns the start and end addresses of an allocation with the specified
+/// layout, placed at the next aligned address. Returns `None` if the
+/// allocation would not be addressable.
+fn bump(next: usize, layout: Layout) -> Option<(usize, usize)> {
+    let start = next.checked_add((layout.align() - next % layout.align()) % layout.align())?;
+    let end = start.checked_add(layout.size())?;
+    Some((start, end))
+}
+
+/// Returns the number of pages to grow memory by, so that a heap ending at
+/// `current` extends to at least `end`. Returns `None` if the grown memory
+/// would not be addressable.
+fn pages_to_grow(current: usize, end: usize) -> Option<usize> {
+    let additional = end.saturating_sub(current);
+    let pages = additional / PAGE_SIZE + usize::from(additional % PAGE_SIZE != 0);
+    memory_end(current / PAGE_SIZE, pages)?;
+    Some(pages)
+}
+
+/// Returns the end address of memory after growing it from `previous` pages
+/// by `pages`, or `None` if it would not be addressable.
+fn memory_end(previous: usize, pages: usize) -> Option<usize> {
+    previous.checked_add(pages)?.checked_mul(PAGE_SIZE)
+}
+
+/// Returns the bounds of the heap, starting after the module's static data.
+#[cfg(target_arch = "wasm32")]
+fn heap() -> (usize, usize) {
+    extern "C" {
+        static __heap_base: u8;
+    }
+
+    // SAFETY: `__heap_base` is defined by the linker, and we only ever use its
+    // address.
+    let start = unsafe { ptr::addr_of!(__heap_base) as usize };
+    let end = core::arch::wasm32::memory_size(0) * PAGE_SIZE;
+    (start, end)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn heap() -> (usize, usize) {
+    (0, 0)
+}
+
+/// Grows memory by the specified number of pages, returning the previous
+/// memory size in pages, or `None` if memory could not be grown.
+#[cfg(target_arch = "wasm32")]
+fn grow(pages: usize) -> Option<usize> {
+    match core::arch::wasm32::memory_grow(0, pages) {
+        usize::MAX => None,
+        previous => Some(previous),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn grow(_: usize) -> Option<usize> {
+    None
+}
+
+/// Parses a decimal integer at compile time.
+const fn parse_usize(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    assert!(
+        !bytes.is_empty(),
+        "SUBGRAPH_INITIAL_MEMORY must not be empty"
+    );
+
+    let mut result = 0_usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let digit = bytes[i];
+        assert!(
+            digit.is_ascii_digit(),
+            "SUBGRAPH_INITIAL_MEMORY must be a decimal integer"
+        );
+        result = match result.checked_mul(10) {
+            Some(value) => value,
+            None => panic!("SUBGRAPH_INITIAL_MEMORY is too large"),
+        };
+        result = match result.checked_add((digit - b'0') as usize) {
+            Some(value) => value,
+            None => panic!("SUBGRAPH_INITIAL_MEMORY is too large"),
+        };
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(size: usize, align: usize) -> Layout {
+        Layout::from_size_align(size, align).unwrap()
+    }
+
+    #[test]
+    fn bump_aligns_allocations() {
+        assert_eq!(bump(0, layout(4, 4)), Some((0, 4)));
+        assert_eq!(bump(5, layout(4, 4)), Some((8, 12)));
+        assert_eq!(bump(8, layout(0, 8)), Some((8, 8)));
+        assert_eq!(bump(9, layout(1, 1)), Some((9, 10)));
+        assert_eq!(bump(17, layout(3, 16)), Some((32, 35)));
+    }
+
+    #[test]
+    fn bump_overflow() {
+        assert_eq!(
+            bump(usize::MAX - 3, layout(3, 4)),
+            Some((usize::MAX - 3, usize::MAX)),
+        );
+        assert_eq!(bump(usize::MAX - 3, layout(4, 4)), None);
+        assert_eq!(bump(usize::MAX - 2, layout(1, 4)), None);
+    }
+
+    #[test]
+    fn pages_to_grow_rounds_up() {
+        assert_eq!(pages_to_grow(PAGE_SIZE, PAGE_SIZE), Some(0));
+        assert_eq!(pages_to_grow(PAGE_SIZE, PAGE_SIZE + 1), Some(1));
+        assert_eq!(pages_to_grow(PAGE_SIZE, 2 * PAGE_SIZE), Some(1));
+        assert_eq!(pages_to_grow(PAGE_SIZE, 3 * PAGE_SIZE - 1), Some(2));
+        assert_eq!(pages_to_grow(0, 1), Some(1));
+    }
+
+    #[test]
+    fn memory_end_overflow() {
+        // The last addressable page, for example page 65535 of a 4 GiB 32-bit
+        // address space, can't be used, as its end is not addressable.
+        let pages = usize::MAX / PAGE_SIZE;
+        assert_eq!(memory_end(pages - 1, 0), Some((pages - 1) * PAGE_SIZE));
+        assert_eq!(memory_end(pages - 1, 1), Some(pages * PAGE_SIZE));
+        assert_eq!(memory_end(pages, 1), None);
+        assert_eq!(memory_end(usize::MAX, 1), None);
+
+        let end = pages * PAGE_SIZE;
+        assert_eq!(pages_to_grow(end - PAGE_SIZE, end), Some(1));
+        assert_eq!(pages_to_grow(end, end + 1), None);
+        assert_eq!(pages_to_grow(end - PAGE_SIZE, usize::MAX), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Arweave block and transaction handler types.
+
+use crate::ffi::{
+    arweave::{AscBlock, AscProofOfAccess, AscTag, AscTransaction, AscTransactionWithBlock},
+    boxed::{AscRef, HostRef},
+    str::AscString,
+    sys,
+};
+
+/// Returns the data of an Arweave transaction by ID.
+///
+/// Returns `None` if the transaction data is not available to the host.
+pub fn transaction_data(tx_id: impl AsRef<str>) -> Option<Vec<u8>> {
+    let tx_id = AscString::new(tx_id.as_ref());
+    let data = unsafe {
+        HostRef::new_nullable(sys::arweave__transaction_data(tx_id.as_ptr()))?.into_ref()
+    };
+    Some(data.as_slice().to_owned())
+}
+
+/// An Arweave block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    pub timestamp: u64,
+    pub last_retarget: u64,
+    pub height: u64,
+    pub indep_hash: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub previous_block: Vec<u8>,
+    pub diff: Vec<u8>,
+    pub hash: Vec<u8>,
+    pub tx_root: Vec<u8>,
+    pub txs: Vec<Vec<u8>>,
+    pub wallet_list: Vec<u8>,
+    pub reward_addr: Vec<u8>,
+    pub tags: Vec<Tag>,
+    pub reward_pool: Vec<u8>,
+    pub weave_size: Vec<u8>,
+    pub block_size: Vec<u8>,
+    pub cumulative_diff: Vec<u8>,
+    pub hash_list_merkle: Vec<u8>,
+    pub poa: ProofOfAccess,
+}
+
+/// A pointer to an Arweave block passed into a block handler.
+pub type BlockPtr = *const AscRef<AscBlock>;
+
+impl Block {
+    fn from_raw(b: &'static AscRef<AscBlock>) -> Self {
+        Self {
+            timestamp: b.timestamp(),
+            last_retarget: b.last_retarget(),
+            height: b.height(),
+            indep_hash: b.indep_hash().as_slice().to_owned(),
+            nonce: b.nonce().as_slice().to_owned(),
+            previous_block: b.previous_block().as_slice().to_owned(),
+            diff: b.diff().as_slice().to_owned(),
+            hash: b.hash().as_slice().to_owned(),
+            tx_root: b.tx_root().as_slice().to_owned(),
+            txs: b
+                .txs()
+                .as_slice()
+                .iter()
+                .map(|tx| tx.as_asc_ref().as_slice().to_owned())
+                .collect(),
+            wallet_list: b.wallet_list().as_slice().to_owned(),
+            reward_addr: b.reward_addr().as_slice().to_owned(),
+            tags: b
+                .tags()
+                .as_slice()
+                .iter()
+                .map(|t| Tag::from_raw(t.as_asc_ref()))
+                .collect(),
+            reward_pool: b.reward_pool().as_slice().to_owned(),
+            weave_size: b.weave_size().as_slice().to_owned(),
+            block_size: b.block_size().as_slice().to_owned(),
+            cumulative_diff: b.cumulative_diff().as_slice().to_owned(),
+            hash_list_merkle: b.hash_list_merkle().as_slice().to_owned(),
+            poa: ProofOfAccess::from_raw(b.poa()),
+        }
+    }
+
+    /// Creates a block from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into an Arweave block handler.
+    pub unsafe fn from_ptr(ptr: BlockPtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+}
+
+/// An Arweave proof of access.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofOfAccess {
+    pub option: String,
+    pub tx_path: Vec<u8>,
+    pub data_path: Vec<u8>,
+    pub chunk: Vec<u8>,
+}
+
+impl ProofOfAccess {
+    fn from_raw(p: &'static AscRef<AscProofOfAccess>) -> Self {
+        Self {
+            option: p.option().to_string_lossy(),
+            tx_path: p.tx_path().as_slice().to_owned(),
+            data_path: p.data_path().as_slice().to_owned(),
+            chunk: p.chunk().as_slice().to_owned(),
+        }
+    }
+}
+
+/// An Arweave transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transaction {
+    pub format: u32,
+    pub id: Vec<u8>,
+    pub last_tx: Vec<u8>,
+    pub owner: Vec<u8>,
+    pub tags: Vec<Tag>,
+    pub target: Vec<u8>,
+    pub quantity: Vec<u8>,
+    pub data: Vec<u8>,
+    pub data_size: Vec<u8>,
+    pub data_root: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub reward: Vec<u8>,
+}
+
+impl Transaction {
+    fn from_raw(t: &'static AscRef<AscTransaction>) -> Self {
+        Self {
+            format: t.format(),
+            id: t.id().as_slice().to_owned(),
+            last_tx: t.last_tx().as_slice().to_owned(),
+            owner: t.owner().as_slice().to_owned(),
+            tags: t
+                .tags()
+                .as_slice()
+                .iter()
+                .map(|t| Tag::from_raw(t.as_asc_ref()))
+                .collect(),
+            target: t.target().as_slice().to_owned(),
+            quantity: t.quantity().as_slice().to_owned(),
+            data: t.data().as_slice().to_owned(),
+            data_size: t.data_size().as_slice().to_owned(),
+            data_root: t.data_root().as_slice().to_owned(),
+            signature: t.signature().as_slice().to_owned(),
+            reward: t.reward().as_slice().to_owned(),
+        }
+    }
+}
+
+/// An Arweave block or transaction tag.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tag {
+    pub name: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+impl Tag {
+    fn from_raw(t: &'static AscRef<AscTag>) -> Self {
+        Self {
+            name: t.name().as_slice().to_owned(),
+            value: t.value().as_slice().to_owned(),
+        }
+    }
+}
+
+/// An Arweave transaction along with its block, passed into transaction
+/// handlers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionWithBlock {
+    pub tx: Transaction,
+    pub block: Block,
+}
+
+/// A pointer to an Arweave transaction passed into a transaction handler.
+pub type TransactionPtr = *const AscRef<AscTransactionWithBlock>;
+
+impl TransactionWithBlock {
+    fn from_raw(t: &'static AscRef<AscTransactionWithBlock>) -> Self {
+        Self {
+            tx: Transaction::from_raw(t.tx()),
+            block: Block::from_raw(t.block()),
+        }
+    }
+
+    /// Creates a transaction from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into an Arweave transaction handler.
+    pub unsafe fn from_ptr(ptr: TransactionPtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+}
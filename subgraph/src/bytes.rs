@@ -0,0 +1,206 @@
+//! Dynamically sized byte arrays.
+
+use crate::{conv, entity, eth};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// A dynamically sized byte array.
+///
+/// This is the target type for converting Ethereum `bytes` values, since
+/// `Vec<u8>` converts from arrays of `uint8` values instead.
+#[derive(Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Returns the bytes as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the inner byte vector.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Bytes")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Bytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let hex = conv::hex(&self.0);
+        f.write_str(&hex)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_owned())
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl From<Bytes> for entity::Value {
+    fn from(bytes: Bytes) -> Self {
+        Self::Bytes(bytes.0)
+    }
+}
+
+impl TryFrom<entity::Value> for Bytes {
+    type Error = entity::ValueError;
+
+    fn try_from(value: entity::Value) -> Result<Self, Self::Error> {
+        match value {
+            entity::Value::Bytes(bytes) => Ok(Self(bytes)),
+            value => Err(entity::ValueError::unexpected("bytes", value.kind())),
+        }
+    }
+}
+
+impl From<Bytes> for eth::Value {
+    fn from(bytes: Bytes) -> Self {
+        Self::Bytes(bytes.0)
+    }
+}
+
+impl TryFrom<eth::Value> for Bytes {
+    type Error = eth::ValueError;
+
+    fn try_from(value: eth::Value) -> Result<Self, Self::Error> {
+        match value {
+            eth::Value::Bytes(bytes) | eth::Value::FixedBytes(bytes) => Ok(Self(bytes)),
+            value => Err(eth::ValueError::unexpected("bytes", value.kind())),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&conv::hex(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("bytes or a hex string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                conv::decode_hex_vec(v)
+                    .map(Bytes)
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Bytes(v.to_owned()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Bytes(v))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Visitor)
+        } else {
+            deserializer.deserialize_byte_buf(Visitor)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde::{
+        de::{
+            value::{BytesDeserializer, Error, StrDeserializer},
+            IntoDeserializer as _,
+        },
+        Deserialize as _,
+    };
+
+    #[test]
+    fn deserialize_from_hex_string() {
+        let de: StrDeserializer<Error> = "0xc0ffee".into_deserializer();
+        assert_eq!(Bytes::deserialize(de).unwrap().0, [0xc0, 0xff, 0xee]);
+
+        let de: StrDeserializer<Error> = "".into_deserializer();
+        assert!(Bytes::deserialize(de).unwrap().is_empty());
+
+        for invalid in ["0xc0f", "0xc0ffeg", "0x\u{e9}\u{e9}"] {
+            let de: StrDeserializer<Error> = invalid.into_deserializer();
+            assert!(Bytes::deserialize(de).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn deserialize_from_bytes() {
+        let de = BytesDeserializer::<Error>::new(&[0xc0, 0xff, 0xee]);
+        assert_eq!(Bytes::deserialize(de).unwrap().0, [0xc0, 0xff, 0xee]);
+    }
+}
@@ -1,18 +1,24 @@
-//! Subgraph type to string conversions.
+//! Subgraph type conversions.
 
-use crate::ffi::{buf::AscTypedArray, sys};
+use crate::ffi::{boxed::HostRef, buf::AscTypedArray, sys};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter, Write as _},
+};
 
 /// Encodes a slice of bytes as a hex string.
 pub fn hex(bytes: impl AsRef<[u8]>) -> String {
     let bytes = AscTypedArray::from_bytes(bytes.as_ref());
-    let str = unsafe { &*sys::type_conversion__bytes_to_hex(bytes.as_ptr()) };
+    let str =
+        unsafe { HostRef::new(sys::type_conversion__bytes_to_hex(bytes.as_ptr())).into_ref() };
     str.to_string_lossy()
 }
 
 /// Encodes a slice of bytes as a hex string.
 pub fn base58(bytes: impl AsRef<[u8]>) -> String {
     let bytes = AscTypedArray::from_bytes(bytes.as_ref());
-    let str = unsafe { &*sys::type_conversion__bytes_to_base58(bytes.as_ptr()) };
+    let str =
+        unsafe { HostRef::new(sys::type_conversion__bytes_to_base58(bytes.as_ptr())).into_ref() };
     str.to_string_lossy()
 }
 
@@ -23,6 +29,138 @@ pub fn base58(bytes: impl AsRef<[u8]>) -> String {
 #[deprecated]
 pub fn string(bytes: impl AsRef<[u8]>) -> String {
     let bytes = AscTypedArray::from_bytes(bytes.as_ref());
-    let str = unsafe { &*sys::type_conversion__bytes_to_string(bytes.as_ptr()) };
+    let str =
+        unsafe { HostRef::new(sys::type_conversion__bytes_to_string(bytes.as_ptr())).into_ref() };
     str.to_string_lossy()
 }
+
+/// An error converting a dynamic value into a Rust type.
+///
+/// This is shared by entity, Ethereum and JSON value conversions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValueError {
+    /// The value is of an unexpected type.
+    Type {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The integer value is out of range for the Rust integer type.
+    Range { target: &'static str },
+    /// The value has an unexpected number of bytes or items.
+    Length { expected: usize, found: usize },
+}
+
+impl ValueError {
+    pub(crate) fn unexpected(expected: &'static str, found: &'static str) -> Self {
+        Self::Type { expected, found }
+    }
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Type { expected, found } => write!(f, "expected {expected} value, found {found}"),
+            Self::Range { target } => write!(f, "integer value out of range for {target}"),
+            Self::Length { expected, found } => {
+                write!(f, "expected value of length {expected}, found {found}")
+            }
+        }
+    }
+}
+
+impl Error for ValueError {}
+
+/// Encodes a slice of bytes as a `0x`-prefixed lowercase hex string.
+///
+/// This produces the same output as [`hex`] without calling into the host.
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Decodes a fixed-size hex string, with an optional `0x` prefix.
+///
+/// Unlike the host conversion functions, this does not abort on invalid input.
+pub(crate) fn decode_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    let mut bytes = [0; N];
+    decode_hex_into(s, &mut bytes)?;
+    Some(bytes)
+}
+
+/// Decodes a dynamically sized hex string, with an optional `0x` prefix.
+#[cfg(feature = "serde")]
+pub(crate) fn decode_hex_vec(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![0; s.strip_prefix("0x").unwrap_or(s).len() / 2];
+    decode_hex_into(s, &mut bytes)?;
+    Some(bytes)
+}
+
+fn decode_hex_into(s: &str, bytes: &mut [u8]) -> Option<()> {
+    let hex = s.strip_prefix("0x").unwrap_or(s).as_bytes();
+    if hex.len() != bytes.len() * 2 {
+        return None;
+    }
+
+    for (byte, digits) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+        let nibble = |digit: u8| (digit as char).to_digit(16);
+        *byte = ((nibble(digits[0])? << 4) | nibble(digits[1])?) as _;
+    }
+    Some(())
+}
+
+/// Serializes fixed-size bytes as a hex string for human readable formats, and
+/// as raw bytes otherwise.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_fixed_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Deserializes fixed-size bytes from either a hex string or raw bytes.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_fixed_bytes<'de, D, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct Visitor<const N: usize>;
+
+    impl<'de, const N: usize> serde::de::Visitor<'de> for Visitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{N} bytes or a hex string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            decode_hex(v).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(Visitor)
+    } else {
+        deserializer.deserialize_bytes(Visitor)
+    }
+}
@@ -0,0 +1,374 @@
+//! Cosmos block, event, transaction and message handler types.
+//!
+//! The host passes Cosmos trigger data to handlers as protobuf-encoded bytes,
+//! which are decoded into the types in this module. Fields that are not
+//! modelled here are skipped when decoding.
+
+use crate::ffi::{
+    boxed::{AscRef, HostRef},
+    types::AscBytes,
+};
+use prost::Message;
+
+/// A pointer to protobuf-encoded Cosmos data passed into a handler.
+pub type DataPtr = *const AscRef<AscBytes>;
+
+/// A pointer to a Cosmos block passed into a block handler.
+pub type BlockPtr = DataPtr;
+
+/// A pointer to Cosmos event data passed into an event handler.
+pub type EventPtr = DataPtr;
+
+/// A pointer to Cosmos transaction data passed into a transaction handler.
+pub type TransactionPtr = DataPtr;
+
+/// A pointer to Cosmos message data passed into a message handler.
+pub type MessagePtr = DataPtr;
+
+/// Decodes protobuf-encoded trigger data passed to a handler.
+///
+/// # Safety
+///
+/// The pointer must be a valid bytes pointer passed into a handler.
+unsafe fn decode<T>(ptr: DataPtr) -> T
+where
+    T: Message + Default,
+{
+    let bytes = HostRef::new(ptr).into_ref().as_slice();
+    T::decode(bytes).expect("invalid Cosmos trigger data")
+}
+
+macro_rules! from_ptr {
+    ($type:ident, $ptr:ident, $handler:literal) => {
+        impl $type {
+            #[doc = concat!("Decodes ", $handler, " data from a raw pointer.")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("This must be a pointer passed into a Cosmos ", $handler, " handler.")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the data is not a valid protobuf encoding.
+            pub unsafe fn from_ptr(ptr: $ptr) -> Self {
+                decode(ptr)
+            }
+        }
+    };
+}
+
+from_ptr!(Block, BlockPtr, "block");
+from_ptr!(EventData, EventPtr, "event");
+from_ptr!(TransactionData, TransactionPtr, "transaction");
+from_ptr!(MessageData, MessagePtr, "message");
+
+/// A Cosmos block.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Block {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+    #[prost(message, repeated, tag = "3")]
+    pub begin_block_events: Vec<Event>,
+    #[prost(message, repeated, tag = "4")]
+    pub end_block_events: Vec<Event>,
+    #[prost(message, repeated, tag = "5")]
+    pub transactions: Vec<TxResult>,
+    #[prost(message, repeated, tag = "6")]
+    pub validator_updates: Vec<ValidatorUpdate>,
+}
+
+/// A Cosmos block containing only its header.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct HeaderOnlyBlock {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+}
+
+/// Event data passed into event handlers.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct EventData {
+    #[prost(message, optional, tag = "1")]
+    pub event: Option<Event>,
+    #[prost(message, optional, tag = "2")]
+    pub block: Option<HeaderOnlyBlock>,
+    #[prost(message, optional, tag = "3")]
+    pub tx: Option<TransactionContext>,
+}
+
+/// Transaction data passed into transaction handlers.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct TransactionData {
+    #[prost(message, optional, tag = "1")]
+    pub tx: Option<TxResult>,
+    #[prost(message, optional, tag = "2")]
+    pub block: Option<HeaderOnlyBlock>,
+}
+
+/// Message data passed into message handlers.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct MessageData {
+    #[prost(message, optional, tag = "1")]
+    pub message: Option<Any>,
+    #[prost(message, optional, tag = "2")]
+    pub block: Option<HeaderOnlyBlock>,
+    #[prost(message, optional, tag = "3")]
+    pub tx: Option<TransactionContext>,
+}
+
+/// The context of the transaction that an event or message belongs to.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct TransactionContext {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub index: u32,
+    #[prost(uint32, tag = "3")]
+    pub code: u32,
+    #[prost(int64, tag = "4")]
+    pub gas_wanted: i64,
+    #[prost(int64, tag = "5")]
+    pub gas_used: i64,
+}
+
+/// A Cosmos block header.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Header {
+    #[prost(message, optional, tag = "1")]
+    pub version: Option<Consensus>,
+    #[prost(string, tag = "2")]
+    pub chain_id: String,
+    #[prost(uint64, tag = "3")]
+    pub height: u64,
+    #[prost(message, optional, tag = "4")]
+    pub time: Option<Timestamp>,
+    #[prost(message, optional, tag = "5")]
+    pub last_block_id: Option<BlockId>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub last_commit_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub data_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "8")]
+    pub validators_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "9")]
+    pub next_validators_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "10")]
+    pub consensus_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "11")]
+    pub app_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "12")]
+    pub last_results_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "13")]
+    pub evidence_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "14")]
+    pub proposer_address: Vec<u8>,
+    #[prost(bytes = "vec", tag = "15")]
+    pub hash: Vec<u8>,
+}
+
+/// Block and application protocol versions.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Consensus {
+    #[prost(uint64, tag = "1")]
+    pub block: u64,
+    #[prost(uint64, tag = "2")]
+    pub app: u64,
+}
+
+/// A point in time, as seconds and nanoseconds since the Unix epoch.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+/// A Cosmos block ID.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct BlockId {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub part_set_header: Option<PartSetHeader>,
+}
+
+/// A Cosmos block part set header.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct PartSetHeader {
+    #[prost(uint32, tag = "1")]
+    pub total: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub hash: Vec<u8>,
+}
+
+/// A Cosmos event.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Event {
+    #[prost(string, tag = "1")]
+    pub event_type: String,
+    #[prost(message, repeated, tag = "2")]
+    pub attributes: Vec<EventAttribute>,
+}
+
+impl Event {
+    /// Returns the value of the first attribute with the specified key.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.as_str())
+    }
+}
+
+/// A Cosmos event attribute.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct EventAttribute {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+    #[prost(bool, tag = "3")]
+    pub index: bool,
+}
+
+/// A Cosmos transaction along with its execution result.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct TxResult {
+    #[prost(uint64, tag = "1")]
+    pub height: u64,
+    #[prost(uint32, tag = "2")]
+    pub index: u32,
+    #[prost(message, optional, tag = "3")]
+    pub result: Option<ResponseDeliverTx>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub hash: Vec<u8>,
+    #[prost(message, optional, tag = "5")]
+    pub tx: Option<Tx>,
+}
+
+/// A Cosmos transaction.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Tx {
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<TxBody>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signatures: Vec<Vec<u8>>,
+}
+
+/// The body of a Cosmos transaction.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct TxBody {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(string, tag = "2")]
+    pub memo: String,
+    #[prost(uint64, tag = "3")]
+    pub timeout_height: u64,
+}
+
+/// The result of executing a Cosmos transaction.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct ResponseDeliverTx {
+    #[prost(uint32, tag = "1")]
+    pub code: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+    #[prost(string, tag = "3")]
+    pub log: String,
+    #[prost(string, tag = "4")]
+    pub info: String,
+    #[prost(int64, tag = "5")]
+    pub gas_wanted: i64,
+    #[prost(int64, tag = "6")]
+    pub gas_used: i64,
+    #[prost(message, repeated, tag = "7")]
+    pub events: Vec<Event>,
+    #[prost(string, tag = "8")]
+    pub codespace: String,
+}
+
+/// A Cosmos validator update.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct ValidatorUpdate {
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub pub_key: Option<PublicKey>,
+    #[prost(int64, tag = "3")]
+    pub power: i64,
+}
+
+/// A Cosmos validator public key.
+///
+/// Only the field for the key type of the validator is set.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct PublicKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub ed25519: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub secp256k1: Vec<u8>,
+}
+
+/// A protobuf-encoded message along with its type URL.
+#[derive(Clone, Eq, PartialEq, Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+impl Any {
+    /// Decodes the message into a concrete protobuf message type.
+    pub fn decode<T>(&self) -> Result<T, prost::DecodeError>
+    where
+        T: Message + Default,
+    {
+        T::decode(self.value.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_tx_result() {
+        // Encoded with the field numbers of graph-node's `TxResult` message.
+        let encoded = [
+            0x08, 0x2a, // height: 42
+            0x10, 0x03, // index: 3
+            0x1a, 0x06, // result:
+            0x08, 0x01, //   code: 1
+            0x1a, 0x02, b'o', b'k', //   log: "ok"
+            0x22, 0x02, 0xab, 0xcd, // hash: 0xabcd
+            0x2a, 0x09, // tx:
+            0x0a, 0x04, //   body:
+            0x12, 0x02, b'h', b'i', //     memo: "hi"
+            0x1a, 0x01, 0x01, //   signatures: [0x01]
+        ];
+
+        let tx = TxResult::decode(encoded.as_slice()).unwrap();
+        assert_eq!(
+            tx,
+            TxResult {
+                height: 42,
+                index: 3,
+                result: Some(ResponseDeliverTx {
+                    code: 1,
+                    log: "ok".to_owned(),
+                    ..Default::default()
+                }),
+                hash: vec![0xab, 0xcd],
+                tx: Some(Tx {
+                    body: Some(TxBody {
+                        memo: "hi".to_owned(),
+                        ..Default::default()
+                    }),
+                    signatures: vec![vec![0x01]],
+                }),
+            },
+        );
+        assert_eq!(tx.encode_to_vec(), encoded);
+    }
+}
@@ -1,14 +1,236 @@
 //! Module containing cryptographic utility functions.
 
-use crate::ffi::{buf::AscTypedArray, sys};
+use crate::{
+    conv, entity, eth,
+    ffi::{
+        boxed::{AscRef, HostRef},
+        buf::AscTypedArray,
+        sys,
+        types::AscBytes,
+    },
+};
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter, LowerHex},
+    str::FromStr,
+};
 
 /// A 32-byte hash.
-pub type Hash = [u8; 32];
+///
+/// This is used for block hashes, transaction hashes and log topics.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Hash(pub [u8; 32]);
+
+impl Hash {
+    /// Returns a hash from its raw byte representation.
+    pub(crate) fn from_raw(bytes: &AscRef<AscBytes>) -> Self {
+        Self(bytes.as_slice().try_into().unwrap())
+    }
+
+    /// Computes the Keccak-256 hash of the specified input bytes.
+    pub fn keccak(data: impl AsRef<[u8]>) -> Self {
+        keccak256(data)
+    }
+
+    /// Creates a hash from a slice of bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is not exactly 32-bytes long.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        let mut result = Self::default();
+        result.0.copy_from_slice(bytes);
+        result
+    }
+
+    /// Returns the hash as a slice of bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for Hash {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Hash")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl Display for Hash {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let hex = conv::hex(self.0.as_slice());
+        f.write_str(&hex)
+    }
+}
+
+impl From<[u8; 32]> for Hash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Hash> for [u8; 32] {
+    fn from(hash: Hash) -> Self {
+        hash.0
+    }
+}
+
+impl FromStr for Hash {
+    type Err = ParseHashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        conv::decode_hex(s).map(Self).ok_or(ParseHashError)
+    }
+}
+
+impl LowerHex for Hash {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Hash> for entity::Value {
+    fn from(hash: Hash) -> Self {
+        Self::Bytes(hash.0.to_vec())
+    }
+}
+
+impl TryFrom<entity::Value> for Hash {
+    type Error = entity::ValueError;
+
+    fn try_from(value: entity::Value) -> Result<Self, Self::Error> {
+        match value {
+            entity::Value::Bytes(bytes) => {
+                let found = bytes.len();
+                let bytes = bytes.try_into().map_err(|_| entity::ValueError::Length {
+                    expected: 32,
+                    found,
+                })?;
+                Ok(Self(bytes))
+            }
+            value => Err(entity::ValueError::unexpected("bytes", value.kind())),
+        }
+    }
+}
+
+impl From<Hash> for eth::Value {
+    fn from(hash: Hash) -> Self {
+        Self::FixedBytes(hash.0.to_vec())
+    }
+}
+
+impl TryFrom<eth::Value> for Hash {
+    type Error = eth::ValueError;
+
+    fn try_from(value: eth::Value) -> Result<Self, Self::Error> {
+        Ok(Self(value.try_into()?))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Hash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        conv::serialize_fixed_bytes(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Hash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        conv::deserialize_fixed_bytes(deserializer).map(Self)
+    }
+}
+
+/// An error parsing a hexadecimal hash string.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParseHashError;
+
+impl Display for ParseHashError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("invalid hexadecimal 32-byte hash")
+    }
+}
+
+impl Error for ParseHashError {}
 
 /// Computes the Keccak-256 hash of the specified input bytes.
 pub fn keccak256(data: impl AsRef<[u8]>) -> Hash {
     let data = data.as_ref();
     let array = AscTypedArray::from_bytes(data);
-    let digest = unsafe { &*sys::crypto__keccak256(array.as_ptr()) };
-    digest.as_slice().try_into().unwrap()
+    let digest = unsafe { HostRef::new(sys::crypto__keccak256(array.as_ptr())).into_ref() };
+    Hash::from_raw(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<[u8; 32], ParseHashError> {
+        s.parse::<Hash>().map(|hash| hash.0)
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let mut bytes = [0; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (i as u8) * 8 + 1;
+        }
+        let hash = Hash(bytes);
+
+        let hex = format!("{hash:x}");
+        assert_eq!(
+            hex,
+            "0109111921293139414951596169717981899199a1a9b1b9c1c9d1d9e1e9f1f9",
+        );
+        assert_eq!(parse(&hex), Ok(bytes));
+
+        let prefixed = format!("{hash:#x}");
+        assert_eq!(prefixed, format!("0x{hex}"));
+        assert_eq!(parse(&prefixed), Ok(bytes));
+
+        assert_eq!(parse(&hex.to_uppercase()), Ok(bytes));
+    }
+
+    #[test]
+    fn parse_invalid_hashes() {
+        let hex = "11".repeat(32);
+        for invalid in [
+            String::new(),
+            "0x".to_owned(),
+            // Wrong length.
+            hex[2..].to_owned(),
+            format!("0x{hex}11"),
+            // Odd number of digits.
+            hex[1..].to_owned(),
+            format!("0x{hex}1"),
+            // Non-hex characters.
+            format!("{}g", &hex[1..]),
+            format!("0x{}g", &hex[1..]),
+            format!("{}\u{e9}", &hex[2..]),
+            // Misplaced prefix.
+            format!("{}0x", &hex[2..]),
+            format!("0X{hex}"),
+        ] {
+            assert_eq!(parse(&invalid), Err(ParseHashError), "{invalid:?}");
+        }
+    }
 }
@@ -3,28 +3,53 @@
 
 use crate::{
     address::Address,
-    entity::{Entity, EntityExt as _},
-    ffi::{str::AscString, sys, value::AscArray},
+    entity::{Entity, FieldError},
+    ffi::{
+        boxed::{AscRef, HostRef},
+        str::AscString,
+        sys,
+        types::AscBytes,
+        value::AscArray,
+    },
 };
 
 /// Data source context.
 pub type Context = Entity;
 
 /// Returns the address of the current data source.
+///
+/// # Panics
+///
+/// Panics if the current data source is not associated with a contract address,
+/// for example for file data sources. Use [`string_param`] instead.
 pub fn address() -> Address {
-    let bytes = unsafe { &*sys::data_source__address() };
+    let bytes = unsafe { HostRef::new(sys::data_source__address()).into_ref() };
     Address::from_raw(bytes)
 }
 
 /// Returns the context of the current data source.
 pub fn context() -> Context {
-    let raw = unsafe { &*sys::data_source__context() };
+    let raw = unsafe { HostRef::new(sys::data_source__context()).into_ref() };
     Entity::from_raw(raw)
 }
 
+/// Returns the parameter of the current data source as a string.
+///
+/// For file data sources, this is the content identifier of the file.
+pub fn string_param() -> String {
+    let bytes = unsafe { HostRef::new(sys::data_source__address()).into_ref() };
+    String::from_utf8_lossy(bytes.as_slice()).into_owned()
+}
+
+/// Returns the ID of the current data source.
+pub fn id() -> String {
+    let str = unsafe { HostRef::new(sys::data_source__id()).into_ref() };
+    str.to_string_lossy()
+}
+
 /// Returns the network name of the current data source.
 pub fn network() -> String {
-    let str = unsafe { &*sys::data_source__network() };
+    let str = unsafe { HostRef::new(sys::data_source__network()).into_ref() };
     str.to_string_lossy()
 }
 
@@ -61,3 +86,197 @@ pub fn create_with_context(
         sys::data_source__create_with_context(name.as_ptr(), params.as_ptr(), context.as_ptr())
     };
 }
+
+/// Creates a new file data source from a named template for the specified
+/// content identifier.
+pub fn create_file(template: impl AsRef<str>, cid: impl AsRef<str>) {
+    create(template, [cid]);
+}
+
+/// A file content pointer for a file data source handler.
+pub type FilePtr = *const AscRef<AscBytes>;
+
+/// Returns the file contents from a raw pointer.
+///
+/// # Safety
+///
+/// This must be a pointer passed into a file data source handler.
+pub unsafe fn file_from_ptr(ptr: FilePtr) -> Vec<u8> {
+    HostRef::new(ptr).into_ref().as_slice().to_owned()
+}
+
+/// A data source template.
+///
+/// Templates are declared with the [`template!`] macro, which ensures that the
+/// template name and context type are only ever specified once.
+///
+/// [`template!`]: crate::template
+pub trait Template {
+    /// The name of the template in the Subgraph manifest.
+    const NAME: &'static str;
+
+    /// The typed data source context for the template.
+    type Context: TemplateContext;
+
+    /// Creates a new data source for a contract address from the template.
+    fn create(address: Address, context: &Self::Context) {
+        create_with_context(Self::NAME, [address.to_string()], &context.to_context());
+    }
+
+    /// Creates a new file data source for a content identifier from the
+    /// template.
+    fn create_file(cid: impl AsRef<str>, context: &Self::Context) {
+        create_with_context(Self::NAME, [cid], &context.to_context());
+    }
+
+    /// Returns the typed context of the current data source.
+    fn context() -> Result<Self::Context, FieldError> {
+        Self::Context::from_context(&context())
+    }
+}
+
+/// A typed data source context.
+pub trait TemplateContext: Sized {
+    /// Converts the typed context into a data source context.
+    fn to_context(&self) -> Context;
+
+    /// Reads the typed context from a data source context.
+    fn from_context(context: &Context) -> Result<Self, FieldError>;
+}
+
+impl TemplateContext for () {
+    fn to_context(&self) -> Context {
+        Context::new()
+    }
+
+    fn from_context(_: &Context) -> Result<Self, FieldError> {
+        Ok(())
+    }
+}
+
+/// Declares a data source template, with an optional typed context.
+///
+/// ```no_run
+/// # use subgraph::address::Address;
+/// subgraph::template! {
+///     /// The Uniswap pair template.
+///     pub struct PairTemplate = "Pair";
+///
+///     /// The context for Uniswap pair data sources.
+///     pub struct PairContext {
+///         pub token0: Address,
+///         pub token1: Address,
+///     }
+/// }
+///
+/// # fn handle_pair_created(pair: Address, token0: Address, token1: Address) {
+/// PairTemplate::create(pair, PairContext { token0, token1 });
+/// # }
+/// # fn handle_swap() {
+/// let PairContext { token0, token1 } = PairTemplate::context().unwrap();
+/// # }
+/// ```
+///
+/// Templates without a context only declare the template type:
+///
+/// ```no_run
+/// subgraph::template! {
+///     pub struct MetadataTemplate = "TokenMetadata";
+/// }
+///
+/// # fn handle_token(cid: &str) {
+/// MetadataTemplate::create_file(cid);
+/// # }
+/// ```
+///
+/// Context fields are stored under their Rust field names, and must be
+/// convertible to and from [`entity::Value`](crate::entity::Value)s.
+///
+/// Note that only the Rust types are checked at compile time. The template
+/// name is passed to the host as is, and is not checked against the templates
+/// declared in the Subgraph manifest.
+#[macro_export]
+macro_rules! template {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident = $template:literal;
+    ) => {
+        $crate::template!(@template [$(#[$attr])*] $vis $name $template ());
+
+        impl $name {
+            /// Creates a new data source for a contract address.
+            $vis fn create(address: $crate::address::Address) {
+                $crate::datasource::create($template, [address.to_string()]);
+            }
+
+            /// Creates a new file data source for a content identifier.
+            $vis fn create_file(cid: impl ::std::convert::AsRef<str>) {
+                $crate::datasource::create_file($template, cid);
+            }
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident = $template:literal;
+
+        $(#[$context_attr:meta])*
+        $context_vis:vis struct $context:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $crate::template!(@template [$(#[$attr])*] $vis $name $template $context);
+
+        impl $name {
+            /// Creates a new data source for a contract address.
+            $vis fn create(address: $crate::address::Address, context: $context) {
+                <Self as $crate::datasource::Template>::create(address, &context);
+            }
+
+            /// Creates a new file data source for a content identifier.
+            $vis fn create_file(cid: impl ::std::convert::AsRef<str>, context: $context) {
+                <Self as $crate::datasource::Template>::create_file(cid, &context);
+            }
+
+            /// Returns the typed context of the current data source.
+            $vis fn context() -> ::std::result::Result<$context, $crate::entity::FieldError> {
+                <Self as $crate::datasource::Template>::context()
+            }
+        }
+
+        $(#[$context_attr])*
+        #[derive(Clone, Debug)]
+        $context_vis struct $context {
+            $($(#[$field_attr])* $field_vis $field: $ty,)*
+        }
+
+        impl $crate::datasource::TemplateContext for $context {
+            fn to_context(&self) -> $crate::datasource::Context {
+                $crate::entity::Entity::builder()
+                    $(.field(
+                        ::std::stringify!($field),
+                        ::std::clone::Clone::clone(&self.$field),
+                    ))*
+                    .build()
+            }
+
+            fn from_context(
+                context: &$crate::datasource::Context,
+            ) -> ::std::result::Result<Self, $crate::entity::FieldError> {
+                ::std::result::Result::Ok(Self {
+                    $($field: context.get(::std::stringify!($field))?,)*
+                })
+            }
+        }
+    };
+
+    (@template [$($attr:tt)*] $vis:vis $name:ident $template:literal $context:ty) => {
+        $($attr)*
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+        $vis struct $name;
+
+        impl $crate::datasource::Template for $name {
+            const NAME: &'static str = $template;
+            type Context = $context;
+        }
+    };
+}
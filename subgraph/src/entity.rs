@@ -1,8 +1,17 @@
 //! Dynamic Subgraph values.
 
+mod conv;
+#[cfg(feature = "serde")]
+mod serde;
+
+#[cfg(feature = "serde")]
+pub use self::serde::{from_entity, to_entity, SerdeError};
+pub use crate::conv::ValueError;
 use crate::{
+    address::Address,
+    crypto::Hash,
     ffi::{
-        boxed::{AscBox, AscRef},
+        boxed::{AscBox, AscRef, HostRef},
         buf::AscTypedArray,
         str::AscString,
         value::{AscArray, AscEntity, AscEntityValue, AscEntityValueData, AscMapEntry},
@@ -10,6 +19,12 @@ use crate::{
     num::{BigDecimal, BigInt},
 };
 use indexmap::IndexMap;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+};
 
 /// A Subgraph entity value.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -23,10 +38,13 @@ pub enum Value {
     Null,
     Bytes(Vec<u8>),
     BigInt(BigInt),
+    Int8(i64),
+    /// A timestamp in microseconds since the Unix epoch.
+    Timestamp(i64),
 }
 
-/// A Subgraph entity.
-pub type Entity = IndexMap<String, Value>;
+/// An entity value pointer for an IPFS callback.
+pub type ValuePtr = *const AscRef<AscEntityValue>;
 
 impl Value {
     /// Creates a new instance from a raw Subgraph value.
@@ -46,9 +64,23 @@ impl Value {
             AscEntityValueData::Null(()) => Self::Null,
             AscEntityValueData::Bytes(value) => Self::Bytes(value.as_slice().to_owned()),
             AscEntityValueData::BigInt(value) => Self::BigInt(BigInt::from_raw(value)),
+            AscEntityValueData::Int8(value) => Self::Int8(value),
+            AscEntityValueData::Timestamp(value) => Self::Timestamp(value),
+            // NOTE: Reading values of unknown kinds as anything else would
+            // silently overwrite them when the entity is stored again.
+            AscEntityValueData::Unknown(kind) => panic!("unknown entity value kind {kind:#x}"),
         }
     }
 
+    /// Creates an entity value from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into an IPFS callback.
+    pub unsafe fn from_ptr(ptr: ValuePtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+
     /// Creates a raw AssemblyScript value.
     pub(crate) fn to_raw(&self) -> AscBox<AscEntityValue> {
         match self {
@@ -62,6 +94,8 @@ impl Value {
             Self::Null => AscEntityValue::null(()),
             Self::Bytes(value) => AscEntityValue::bytes(AscTypedArray::from_bytes(value)),
             Self::BigInt(value) => AscEntityValue::bigint(value.as_raw().to_owned()),
+            Self::Int8(value) => AscEntityValue::int8(*value),
+            Self::Timestamp(value) => AscEntityValue::timestamp(*value),
         }
     }
 
@@ -135,19 +169,112 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Returns the entity value as an int8, or `None` if the value the wrong
+    /// type.
+    pub fn as_int8(&self) -> Option<i64> {
+        match self {
+            Self::Int8(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the entity value as a timestamp in microseconds since the Unix
+    /// epoch, or `None` if the value the wrong type.
+    pub fn as_timestamp(&self) -> Option<i64> {
+        match self {
+            Self::Timestamp(value) => Some(*value),
+            _ => None,
+        }
+    }
 }
 
-/// [`Entity`] extension trait.
-pub(crate) trait EntityExt: Sized {
-    /// Creates a new entity from a raw Subgraph key-value map.
-    fn from_raw(raw: &'static AscRef<AscEntity>) -> Self;
+/// A value that can be used as an entity ID.
+///
+/// The host identifies entities by string IDs, so IDs are encoded the way
+/// graph-node expects for the schema's ID type: `ID` and `String` IDs are used
+/// as is, `Bytes` IDs are encoded as `0x`-prefixed lowercase hex and `Int8` IDs
+/// are encoded in decimal.
+pub trait EntityId {
+    /// Returns the string encoding of the entity ID.
+    fn to_entity_id(&self) -> String;
+}
 
-    /// Creates a new map from a raw Subgraph key-value map.
-    fn to_raw(&self) -> AscBox<AscEntity>;
+impl<T> EntityId for &T
+where
+    T: EntityId + ?Sized,
+{
+    fn to_entity_id(&self) -> String {
+        (**self).to_entity_id()
+    }
+}
+
+impl EntityId for str {
+    fn to_entity_id(&self) -> String {
+        self.to_owned()
+    }
+}
+
+impl EntityId for String {
+    fn to_entity_id(&self) -> String {
+        self.clone()
+    }
+}
+
+impl EntityId for [u8] {
+    fn to_entity_id(&self) -> String {
+        crate::conv::encode_hex(self)
+    }
+}
+
+impl EntityId for Vec<u8> {
+    fn to_entity_id(&self) -> String {
+        crate::conv::encode_hex(self)
+    }
+}
+
+impl EntityId for Address {
+    fn to_entity_id(&self) -> String {
+        crate::conv::encode_hex(&self.0)
+    }
 }
 
-impl EntityExt for Entity {
-    fn from_raw(raw: &'static AscRef<AscEntity>) -> Self {
+impl EntityId for Hash {
+    fn to_entity_id(&self) -> String {
+        crate::conv::encode_hex(&self.0)
+    }
+}
+
+impl EntityId for i64 {
+    fn to_entity_id(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// A Subgraph entity.
+///
+/// This is a map of field names to entity values, that additionally keeps
+/// track of the entity type name (when known) in order to provide more useful
+/// errors when accessing fields.
+#[derive(Clone, Debug, Default)]
+pub struct Entity {
+    name: Option<String>,
+    data: IndexMap<String, Value>,
+}
+
+impl Entity {
+    /// Creates a new empty entity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a builder for creating an entity.
+    pub fn builder() -> EntityBuilder {
+        EntityBuilder::default()
+    }
+
+    /// Creates a new entity from a raw Subgraph key-value map.
+    pub(crate) fn from_raw(raw: &'static AscRef<AscEntity>) -> Self {
         raw.entries()
             .iter()
             .map(|entry| {
@@ -160,11 +287,323 @@ impl EntityExt for Entity {
             .collect()
     }
 
-    fn to_raw(&self) -> AscBox<AscEntity> {
+    /// Creates a new raw Subgraph key-value map from an entity.
+    pub(crate) fn to_raw(&self) -> AscBox<AscEntity> {
         AscEntity::new(
-            self.iter()
+            self.data
+                .iter()
                 .map(|(key, value)| AscMapEntry::new(AscString::new(key), value.to_raw()))
                 .collect(),
         )
     }
+
+    /// Sets the entity type name used for error reporting.
+    ///
+    /// Entities returned by [`store::get`](crate::store::get) are already
+    /// tagged with their type name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns the entity type name, if known.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the value of the specified field converted to `T`.
+    ///
+    /// Missing fields are treated as `null` values, meaning that they can be
+    /// read as `Option<T>`. For any other type, an error is returned.
+    pub fn get<T>(&self, field: &str) -> Result<T, FieldError>
+    where
+        T: TryFrom<Value, Error = ValueError>,
+    {
+        match self.data.get(field) {
+            Some(value) => T::try_from(value.clone())
+                .map_err(|err| self.field_error(field, FieldErrorKind::Value(err))),
+            None => T::try_from(Value::Null)
+                .map_err(|_| self.field_error(field, FieldErrorKind::Missing)),
+        }
+    }
+
+    /// Sets the value of the specified field, returning the previous value if
+    /// there was one.
+    pub fn set(&mut self, field: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        self.data.insert(field.into(), value.into())
+    }
+
+    fn field_error(&self, field: &str, kind: FieldErrorKind) -> FieldError {
+        FieldError {
+            entity: self.name.clone(),
+            id: self.data.get("id").and_then(|id| match id {
+                Value::String(id) => Some(id.clone()),
+                Value::Int(id) => Some(id.to_string()),
+                Value::Int8(id) => Some(id.to_string()),
+                Value::Bytes(id) => Some(crate::conv::encode_hex(id)),
+                _ => None,
+            }),
+            field: field.to_owned(),
+            kind,
+        }
+    }
+}
+
+impl Deref for Entity {
+    type Target = IndexMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for Entity {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl Eq for Entity {}
+
+impl From<IndexMap<String, Value>> for Entity {
+    fn from(data: IndexMap<String, Value>) -> Self {
+        Self { name: None, data }
+    }
+}
+
+impl FromIterator<(String, Value)> for Entity {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        Self::from(iter.into_iter().collect::<IndexMap<_, _>>())
+    }
+}
+
+impl IntoIterator for Entity {
+    type Item = (String, Value);
+    type IntoIter = indexmap::map::IntoIter<String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl PartialEq for Entity {
+    fn eq(&self, other: &Self) -> bool {
+        // NOTE: The entity type name is only used for error reporting, so don't
+        // consider it for equality.
+        self.data == other.data
+    }
+}
+
+/// A builder for [`Entity`] values.
+#[derive(Clone, Debug, Default)]
+pub struct EntityBuilder {
+    entity: Entity,
+}
+
+impl EntityBuilder {
+    /// Adds a field to the entity.
+    pub fn field(mut self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.entity.set(field, value);
+        self
+    }
+
+    /// Builds the entity.
+    pub fn build(self) -> Entity {
+        self.entity
+    }
+}
+
+/// An error accessing an entity field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldError {
+    entity: Option<String>,
+    id: Option<String>,
+    field: String,
+    kind: FieldErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum FieldErrorKind {
+    Missing,
+    Value(ValueError),
+}
+
+impl FieldError {
+    /// Returns the entity type name, if known.
+    pub fn entity(&self) -> Option<&str> {
+        self.entity.as_deref()
+    }
+
+    /// Returns the entity ID, if known.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the name of the field that could not be accessed.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns `true` if the error was caused by a missing field.
+    pub fn is_missing(&self) -> bool {
+        matches!(self.kind, FieldErrorKind::Missing)
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "field {:?} of ", self.field)?;
+        match &self.entity {
+            Some(entity) => write!(f, "{entity} entity")?,
+            None => f.write_str("entity")?,
+        }
+        if let Some(id) = &self.id {
+            write!(f, " {id:?}")?;
+        }
+        match &self.kind {
+            FieldErrorKind::Missing => f.write_str(" is missing"),
+            FieldErrorKind::Value(err) => write!(f, ": {err}"),
+        }
+    }
+}
+
+impl Error for FieldError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            FieldErrorKind::Missing => None,
+            FieldErrorKind::Value(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token() -> Entity {
+        Entity::builder()
+            .field("id", "token")
+            .field("symbol", "GRT")
+            .field("decimals", 18)
+            .field("holders", Some(vec![1, 2]))
+            .field("owner", None::<String>)
+            .build()
+            .with_name("Token")
+    }
+
+    #[test]
+    fn builder() {
+        let token = token();
+        assert_eq!(token.name(), Some("Token"));
+        assert_eq!(
+            token.keys().collect::<Vec<_>>(),
+            ["id", "symbol", "decimals", "holders", "owner"],
+        );
+        assert!(matches!(token["decimals"], Value::Int(18)));
+        assert!(matches!(token["owner"], Value::Null));
+    }
+
+    #[test]
+    fn entity_ids() {
+        assert_eq!("token".to_entity_id(), "token");
+        assert_eq!(String::from("token").to_entity_id(), "token");
+        assert_eq!((&&"token").to_entity_id(), "token");
+        assert_eq!(vec![0x01_u8, 0xab].to_entity_id(), "0x01ab");
+        assert_eq!([0xff_u8][..].to_entity_id(), "0xff");
+        assert_eq!(Vec::<u8>::new().to_entity_id(), "0x");
+        assert_eq!(
+            Address([0xab; 20]).to_entity_id(),
+            format!("0x{}", "ab".repeat(20)),
+        );
+        assert_eq!(
+            Hash([0x0c; 32]).to_entity_id(),
+            format!("0x{}", "0c".repeat(32))
+        );
+        assert_eq!(42_i64.to_entity_id(), "42");
+        assert_eq!(i64::MIN.to_entity_id(), "-9223372036854775808");
+    }
+
+    #[test]
+    fn set_fields() {
+        let mut entity = Entity::new();
+        assert!(entity.set("count", 1).is_none());
+        assert!(matches!(entity.set("count", 2), Some(Value::Int(1))));
+        assert!(matches!(entity["count"], Value::Int(2)));
+    }
+
+    #[test]
+    fn get_fields() {
+        let token = token();
+        assert_eq!(token.get::<String>("symbol").unwrap(), "GRT");
+        assert_eq!(token.get::<u16>("decimals").unwrap(), 18);
+        assert_eq!(token.get::<Vec<i32>>("holders").unwrap(), [1, 2]);
+        assert_eq!(token.get::<Option<String>>("owner").unwrap(), None);
+        assert_eq!(token.get::<Option<String>>("name").unwrap(), None);
+    }
+
+    #[test]
+    fn missing_field_error() {
+        let err = token().get::<String>("name").unwrap_err();
+        assert!(err.is_missing());
+        assert_eq!(err.entity(), Some("Token"));
+        assert_eq!(err.id(), Some("token"));
+        assert_eq!(err.field(), "name");
+        assert!(err.source().is_none());
+        assert_eq!(
+            err.to_string(),
+            r#"field "name" of Token entity "token" is missing"#,
+        );
+    }
+
+    #[test]
+    fn wrong_type_field_error() {
+        let err = token().get::<bool>("symbol").unwrap_err();
+        assert!(!err.is_missing());
+        assert_eq!(err.field(), "symbol");
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "expected bool value, found string",
+        );
+        assert_eq!(
+            err.to_string(),
+            r#"field "symbol" of Token entity "token": expected bool value, found string"#,
+        );
+
+        let err = token().get::<i8>("holders").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"field "holders" of Token entity "token": expected integer value, found array"#,
+        );
+
+        let err = token().get::<String>("owner").unwrap_err();
+        assert!(!err.is_missing());
+        assert_eq!(
+            err.to_string(),
+            r#"field "owner" of Token entity "token": expected string value, found null"#,
+        );
+    }
+
+    #[test]
+    fn field_error_without_name_or_id() {
+        let entity = Entity::builder().field("count", 300).build();
+        let err = entity.get::<i8>("count").unwrap_err();
+        assert_eq!(err.entity(), None);
+        assert_eq!(err.id(), None);
+        assert_eq!(
+            err.to_string(),
+            r#"field "count" of entity: integer value out of range for i8"#,
+        );
+
+        let entity = Entity::builder().field("id", Value::Int8(7)).build();
+        let err = entity.get::<String>("count").unwrap_err();
+        assert_eq!(err.id(), Some("7"));
+        assert_eq!(err.to_string(), r#"field "count" of entity "7" is missing"#);
+
+        let entity = Entity::builder().field("id", vec![0x01_u8, 0xab]).build();
+        let err = entity.get::<String>("count").unwrap_err();
+        assert_eq!(err.id(), Some("0x01ab"));
+    }
 }
@@ -0,0 +1,355 @@
+//! Conversions between entity values and Rust types.
+
+use super::Value;
+use crate::{
+    conv::ValueError,
+    eth,
+    num::{BigDecimal, BigInt},
+};
+
+impl Value {
+    /// Returns a human readable name of the kind of entity value.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::String(_) => "string",
+            Self::Int(_) => "int",
+            Self::BigDecimal(_) => "big decimal",
+            Self::Bool(_) => "bool",
+            Self::Array(_) => "array",
+            Self::Null => "null",
+            Self::Bytes(_) => "bytes",
+            Self::BigInt(_) => "big int",
+            Self::Int8(_) => "int8",
+            Self::Timestamp(_) => "timestamp",
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+macro_rules! impl_from_int_for_value {
+    ($($variant:ident => $($t:ty),*;)*) => {$($(
+        impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                Self::$variant(value.into())
+            }
+        }
+    )*)*};
+}
+
+// NOTE: There are intentionally no `u8` conversions, so that `Vec<u8>` can be
+// converted to and from `Bytes` instead of an array of `Int`s. Additionally,
+// 64-bit integers convert to `BigInt` and not `Int8`, as the two are different
+// schema types and `BigInt` is the historical mapping. `Int8` fields must be
+// set explicitly with `Value::Int8`.
+impl_from_int_for_value! {
+    Int => i8, i16, i32, u16;
+    BigInt => u32, i64, u64, i128, u128;
+}
+
+impl From<BigDecimal> for Value {
+    fn from(value: BigDecimal) -> Self {
+        Self::BigDecimal(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T> From<Vec<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Vec<T>) -> Self {
+        Self::Array(value.into_iter().map(T::into).collect())
+    }
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map(T::into).unwrap_or_default()
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(value: &[u8]) -> Self {
+        Self::Bytes(value.to_owned())
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        Self::BigInt(value)
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(value) => Ok(value),
+            value => Err(ValueError::unexpected("string", value.kind())),
+        }
+    }
+}
+
+macro_rules! impl_try_from_value_for_int {
+    ($($t:ty),*) => {$(
+        impl TryFrom<Value> for $t {
+            type Error = ValueError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                let range = ValueError::Range {
+                    target: stringify!($t),
+                };
+                match value {
+                    Value::Int(value) => value.try_into().map_err(|_| range),
+                    Value::BigInt(value) => value.try_into().map_err(|_| range),
+                    Value::Int8(value) => value.try_into().map_err(|_| range),
+                    value => Err(ValueError::unexpected("integer", value.kind())),
+                }
+            }
+        }
+    )*};
+}
+
+impl_try_from_value_for_int!(i8, i16, i32, i64, i128, u16, u32, u64, u128);
+
+impl TryFrom<Value> for BigDecimal {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::BigDecimal(value) => Ok(value),
+            value => Err(ValueError::unexpected("big decimal", value.kind())),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bool(value) => Ok(value),
+            value => Err(ValueError::unexpected("bool", value.kind())),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value, Error = ValueError>,
+{
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(value) => value.into_iter().map(T::try_from).collect(),
+            value => Err(ValueError::unexpected("array", value.kind())),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Option<T>
+where
+    T: TryFrom<Value, Error = ValueError>,
+{
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(value) => Ok(value),
+            value => Err(ValueError::unexpected("bytes", value.kind())),
+        }
+    }
+}
+
+impl TryFrom<Value> for BigInt {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Int(value) => Ok(value.into()),
+            Value::BigInt(value) => Ok(value),
+            Value::Int8(value) => Ok(value.into()),
+            value => Err(ValueError::unexpected("big int", value.kind())),
+        }
+    }
+}
+
+impl TryFrom<Value> for eth::Value {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(value) => Ok(Self::String(value)),
+            Value::Int(value) => Ok(Self::Int(value.into())),
+            Value::Bool(value) => Ok(Self::Bool(value)),
+            Value::Array(value) => Ok(Self::Array(
+                value
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Value::Bytes(value) => Ok(Self::Bytes(value)),
+            Value::BigInt(value) => Ok(Self::Int(value)),
+            Value::Int8(value) => Ok(Self::Int(value.into())),
+            value => Err(ValueError::unexpected("Ethereum compatible", value.kind())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+
+    fn big_int(value: Value) -> Option<i128> {
+        match value {
+            Value::BigInt(value) => i128::try_from(&value).ok(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn from_rust_values() {
+        assert!(matches!(Value::from("a"), Value::String(s) if s == "a"));
+        assert!(matches!(Value::from(-1_i8), Value::Int(-1)));
+        assert!(matches!(Value::from(u16::MAX), Value::Int(0xffff)));
+        assert_eq!(big_int(Value::from(u32::MAX)), Some(u32::MAX.into()));
+        assert_eq!(big_int(Value::from(i64::MIN)), Some(i64::MIN.into()));
+        assert_eq!(big_int(Value::from(u128::MAX >> 1)), Some(i128::MAX));
+        assert!(matches!(Value::from(true), Value::Bool(true)));
+        assert!(matches!(Value::from(vec![1_u8, 2]), Value::Bytes(b) if b == [1, 2]));
+        assert!(matches!(
+            Value::from(vec!["a", "b"]),
+            Value::Array(a) if matches!(&a[..], [Value::String(a), Value::String(b)] if a == "a" && b == "b")
+        ));
+        assert!(matches!(Value::from(None::<i32>), Value::Null));
+        assert!(matches!(Value::from(Some(1)), Value::Int(1)));
+    }
+
+    #[test]
+    fn into_rust_values() {
+        assert_eq!(String::try_from(Value::from("a")), Ok("a".to_owned()));
+        assert_eq!(i32::try_from(Value::Int(-1)), Ok(-1));
+        assert_eq!(u16::try_from(Value::Int(-1)).ok(), None);
+        assert_eq!(i64::try_from(Value::Int8(i64::MAX)), Ok(i64::MAX));
+        assert_eq!(u64::try_from(Value::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(
+            i32::try_from(Value::Int8(i64::MAX)),
+            Err(ValueError::Range { target: "i32" }),
+        );
+        assert_eq!(
+            i32::try_from(Value::Bool(true)),
+            Err(ValueError::unexpected("integer", "bool")),
+        );
+        assert_eq!(Vec::<u8>::try_from(Value::Bytes(vec![1])), Ok(vec![1]));
+        assert_eq!(
+            Address::try_from(Value::from(Address([1; 20]))).map(|a| a.0),
+            Ok([1; 20]),
+        );
+        assert_eq!(
+            Address::try_from(Value::Bytes(vec![1])).map(|a| a.0),
+            Err(ValueError::Length {
+                expected: 20,
+                found: 1
+            }),
+        );
+        assert_eq!(
+            Vec::<i32>::try_from(Value::Array(vec![Value::Int(1), Value::Int(2)])),
+            Ok(vec![1, 2]),
+        );
+        assert_eq!(
+            Vec::<i32>::try_from(Value::Array(vec![Value::Int(1), Value::Null])),
+            Err(ValueError::unexpected("integer", "null")),
+        );
+        assert_eq!(Option::<bool>::try_from(Value::Null), Ok(None));
+        assert_eq!(Option::<bool>::try_from(Value::Bool(true)), Ok(Some(true)));
+        assert_eq!(
+            bool::try_from(Value::Null),
+            Err(ValueError::unexpected("bool", "null")),
+        );
+    }
+
+    #[test]
+    fn from_ethereum_values() {
+        let value = |value| Value::try_from(value);
+
+        assert!(matches!(
+            value(eth::Value::Address(Address([1; 20]))),
+            Ok(Value::Bytes(b)) if b == [1; 20]
+        ));
+        assert!(matches!(
+            value(eth::Value::FixedBytes(vec![2; 32])),
+            Ok(Value::Bytes(b)) if b == [2; 32]
+        ));
+        assert_eq!(
+            value(eth::Value::Uint(BigInt::from(42)))
+                .ok()
+                .and_then(big_int),
+            Some(42),
+        );
+        assert!(matches!(
+            value(eth::Value::Array(vec![eth::Value::Bool(true)])),
+            Ok(Value::Array(a)) if matches!(&a[..], [Value::Bool(true)])
+        ));
+        assert_eq!(
+            value(eth::Value::Tuple(vec![])).err(),
+            Some(ValueError::unexpected("entity compatible", "tuple")),
+        );
+    }
+
+    #[test]
+    fn into_ethereum_values() {
+        let value = |value| eth::Value::try_from(value);
+
+        assert!(matches!(
+            value(Value::Int8(-1)),
+            Ok(eth::Value::Int(v)) if i64::try_from(&v) == Ok(-1)
+        ));
+        assert!(matches!(
+            value(Value::Bytes(vec![1])),
+            Ok(eth::Value::Bytes(b)) if b == [1]
+        ));
+        assert_eq!(
+            value(Value::Null).err(),
+            Some(ValueError::unexpected("Ethereum compatible", "null")),
+        );
+    }
+}
@@ -0,0 +1,806 @@
+//! Serde support for converting Rust types to and from entities.
+
+use super::{Entity, Value};
+use crate::num::{
+    serde::{BIG_DECIMAL_TOKEN, BIG_INT_TOKEN},
+    BigDecimal, BigInt,
+};
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer, StringDeserializer},
+        DeserializeOwned, Deserializer, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, Impossible, Serialize, Serializer},
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+/// Converts a Rust value into an entity.
+///
+/// The value must serialize to a struct or a map with string keys. Fields are
+/// converted to entity values as follows:
+/// - `bool` to `Bool`
+/// - `i8`, `i16`, `i32`, `u8` and `u16` to `Int`
+/// - `u32`, `i64`, `u64`, `i128`, `u128` and [`BigInt`] to `BigInt`
+/// - `f32`, `f64` and [`BigDecimal`] to `BigDecimal`
+/// - strings, characters and unit enum variants to `String`
+/// - bytes (including addresses and hashes) to `Bytes`
+/// - `None` and units to `Null`
+/// - sequences and tuples to `Array`
+///
+/// Note that `Vec<u8>` serializes to an array of integers; use
+/// [`Bytes`](crate::bytes::Bytes) for `Bytes` fields. `Int8` fields must be
+/// set explicitly on the resulting entity, as 64-bit integers serialize to
+/// `BigInt`.
+pub fn to_entity<T>(value: &T) -> Result<Entity, SerdeError>
+where
+    T: Serialize + ?Sized,
+{
+    match value.serialize(ValueSerializer)? {
+        Output::Entity(entity) => Ok(entity),
+        Output::Value(value) => Err(SerdeError(format!(
+            "expected struct or map, found {}",
+            value.kind(),
+        ))),
+    }
+}
+
+/// Converts an entity into a Rust value.
+///
+/// This is the inverse of [`to_entity`]. Missing fields are treated as `null`
+/// values, so they can be deserialized as `Option`s.
+pub fn from_entity<T>(entity: Entity) -> Result<T, SerdeError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(entity)
+}
+
+/// An error converting a Rust value to or from an entity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SerdeError(String);
+
+impl Display for SerdeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+/// The output of the entity value serializer.
+///
+/// Structs and maps serialize to entities, which are only valid at the top
+/// level, as entities cannot be nested.
+enum Output {
+    Value(Value),
+    Entity(Entity),
+}
+
+impl Output {
+    fn into_value(self) -> Result<Value, SerdeError> {
+        match self {
+            Self::Value(value) => Ok(value),
+            Self::Entity(_) => Err(SerdeError(
+                "nested structs and maps are not supported in entities".to_owned(),
+            )),
+        }
+    }
+}
+
+struct ValueSerializer;
+
+macro_rules! serialize_value {
+    ($($method:ident($t:ty) => $variant:ident;)*) => {$(
+        fn $method(self, v: $t) -> Result<Self::Ok, Self::Error> {
+            Ok(Output::Value(Value::$variant(v.into())))
+        }
+    )*};
+}
+
+impl Serializer for ValueSerializer {
+    type Ok = Output;
+    type Error = SerdeError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = Impossible<Output, SerdeError>;
+    type SerializeMap = SerializeEntity;
+    type SerializeStruct = SerializeEntity;
+    type SerializeStructVariant = Impossible<Output, SerdeError>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    serialize_value! {
+        serialize_bool(bool) => Bool;
+        serialize_i8(i8) => Int;
+        serialize_i16(i16) => Int;
+        serialize_i32(i32) => Int;
+        serialize_i64(i64) => BigInt;
+        serialize_i128(i128) => BigInt;
+        serialize_u8(u8) => Int;
+        serialize_u16(u16) => Int;
+        serialize_u32(u32) => BigInt;
+        serialize_u64(u64) => BigInt;
+        serialize_u128(u128) => BigInt;
+        serialize_str(&str) => String;
+        serialize_bytes(&[u8]) => Bytes;
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        if !v.is_finite() {
+            return Err(SerdeError(format!("non-finite decimal value {v}")));
+        }
+        Ok(Output::Value(Value::BigDecimal(BigDecimal::parse(
+            v.to_string(),
+        ))))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Value(Value::String(v.to_string())))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Value(Value::Null))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Value(Value::Null))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Value(Value::Null))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Value(Value::String(variant.to_owned())))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = match (name, value.serialize(self)?.into_value()?) {
+            (BIG_INT_TOKEN, Value::Bytes(bytes)) => {
+                Value::BigInt(BigInt::from_signed_bytes_le(bytes))
+            }
+            (BIG_DECIMAL_TOKEN, Value::Array(parts)) => {
+                let mut parts = parts.into_iter();
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(Value::BigInt(digits)), Some(Value::BigInt(exp)), None) => {
+                        Value::BigDecimal(BigDecimal::from_parts(digits, exp))
+                    }
+                    _ => return Err(SerdeError("invalid big decimal".to_owned())),
+                }
+            }
+            (_, value) => value,
+        };
+        Ok(Output::Value(value))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(unsupported_variant(name, variant))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeArray(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(unsupported_variant(name, variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeEntity::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(SerializeEntity::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(unsupported_variant(name, variant))
+    }
+}
+
+fn unsupported_variant(name: &str, variant: &str) -> SerdeError {
+    SerdeError(format!(
+        "unsupported enum variant {name}::{variant}; only unit variants are supported"
+    ))
+}
+
+struct SerializeArray(Vec<Value>);
+
+impl SerializeArray {
+    fn push<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.0.push(value.serialize(ValueSerializer)?.into_value()?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Output, SerdeError> {
+        Ok(Output::Value(Value::Array(self.0)))
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Output;
+    type Error = SerdeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Output;
+    type Error = SerdeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Output;
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+#[derive(Default)]
+struct SerializeEntity {
+    entity: Entity,
+    key: Option<String>,
+}
+
+impl SerializeEntity {
+    fn insert<T>(&mut self, key: String, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = value.serialize(ValueSerializer)?.into_value()?;
+        self.entity.set(key, value);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeEntity {
+    type Ok = Output;
+    type Error = SerdeError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        match key.serialize(ValueSerializer)?.into_value()? {
+            Value::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            key => Err(SerdeError(format!(
+                "expected string entity field name, found {}",
+                key.kind(),
+            ))),
+        }
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Entity(self.entity))
+    }
+}
+
+impl ser::SerializeStruct for SerializeEntity {
+    type Ok = Output;
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Output::Entity(self.entity))
+    }
+}
+
+impl<'de> Deserializer<'de> for Entity {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(MapDeserializer::new(self.data.into_iter()))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Entity {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(value) => visitor.visit_string(value),
+            Value::Int(value) => visitor.visit_i32(value),
+            Value::BigDecimal(value) => visitor.visit_string(value.to_string()),
+            Value::Bool(value) => visitor.visit_bool(value),
+            Value::Array(value) => visitor.visit_seq(SeqDeserializer::new(value.into_iter())),
+            Value::Null => visitor.visit_unit(),
+            Value::Bytes(value) => visitor.visit_byte_buf(value),
+            Value::BigInt(value) => {
+                if let Ok(value) = i64::try_from(&value) {
+                    visitor.visit_i64(value)
+                } else if let Ok(value) = u64::try_from(&value) {
+                    visitor.visit_u64(value)
+                } else if let Ok(value) = i128::try_from(&value) {
+                    visitor.visit_i128(value)
+                } else if let Ok(value) = u128::try_from(&value) {
+                    visitor.visit_u128(value)
+                } else {
+                    visitor.visit_string(value.to_string())
+                }
+            }
+            Value::Int8(value) | Value::Timestamp(value) => visitor.visit_i64(value),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (name, self) {
+            (BIG_INT_TOKEN, Value::BigInt(value)) => {
+                visitor.visit_byte_buf(value.to_signed_bytes_le())
+            }
+            (BIG_DECIMAL_TOKEN, Value::BigDecimal(value)) => {
+                let (digits, exp) = value.to_parts();
+                visitor.visit_seq(SeqDeserializer::new(
+                    [Value::BigInt(digits), Value::BigInt(exp)].into_iter(),
+                ))
+            }
+            (BIG_INT_TOKEN | BIG_DECIMAL_TOKEN, value) => value.deserialize_any(visitor),
+            (_, value) => visitor.visit_newtype_struct(value),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(variant) => {
+                visitor.visit_enum(StringDeserializer::<SerdeError>::new(variant))
+            }
+            value => Err(de::Error::invalid_type(
+                de::Unexpected::Other(value.kind()),
+                &"unit enum variant",
+            )),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    /// Helper for (de)serializing raw bytes.
+    #[derive(Debug, Eq, PartialEq)]
+    struct Raw(Vec<u8>);
+
+    impl Serialize for Raw {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Raw {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct RawVisitor;
+
+            impl<'de> Visitor<'de> for RawVisitor {
+                type Value = Raw;
+
+                fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                    f.write_str("bytes")
+                }
+
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                    Ok(Raw(v))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(RawVisitor)
+        }
+    }
+
+    #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+    enum Kind {
+        Mint,
+        Burn,
+    }
+
+    #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+    struct Transfer {
+        id: String,
+        #[serde(rename = "from")]
+        sender: Option<Raw>,
+        kind: Kind,
+        count: i32,
+        amount: u64,
+        memo: Option<String>,
+        tags: Vec<String>,
+        data: Raw,
+    }
+
+    fn transfer() -> Transfer {
+        Transfer {
+            id: "transfer".to_owned(),
+            sender: Some(Raw(vec![1; 20])),
+            kind: Kind::Burn,
+            count: -1,
+            amount: u64::MAX,
+            memo: None,
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            data: Raw(vec![0xc0, 0xff, 0xee]),
+        }
+    }
+
+    #[test]
+    fn serialize_entity() {
+        let entity = to_entity(&transfer()).unwrap();
+
+        assert_eq!(
+            entity.keys().collect::<Vec<_>>(),
+            ["id", "from", "kind", "count", "amount", "memo", "tags", "data"],
+        );
+        assert!(matches!(&entity["id"], Value::String(id) if id == "transfer"));
+        assert!(matches!(&entity["from"], Value::Bytes(from) if from == &[1; 20]));
+        assert!(matches!(&entity["kind"], Value::String(kind) if kind == "Burn"));
+        assert!(matches!(entity["count"], Value::Int(-1)));
+        assert!(matches!(
+            &entity["amount"],
+            Value::BigInt(amount) if u64::try_from(amount) == Ok(u64::MAX)
+        ));
+        assert!(matches!(entity["memo"], Value::Null));
+        assert!(matches!(
+            &entity["tags"],
+            Value::Array(tags)
+                if matches!(&tags[..], [Value::String(a), Value::String(b)] if a == "a" && b == "b")
+        ));
+        assert!(matches!(&entity["data"], Value::Bytes(data) if data == &[0xc0, 0xff, 0xee]));
+    }
+
+    #[test]
+    fn deserialize_entity() {
+        let entity = to_entity(&transfer()).unwrap();
+        assert_eq!(from_entity::<Transfer>(entity).unwrap(), transfer());
+
+        // Missing fields are read as nulls.
+        let mut entity = to_entity(&transfer()).unwrap();
+        entity.shift_remove("from");
+        entity.shift_remove("memo");
+        let value = from_entity::<Transfer>(entity).unwrap();
+        assert_eq!(value.sender, None);
+        assert_eq!(value.memo, None);
+    }
+
+    #[test]
+    fn deserialize_entity_integers() {
+        let entity = Entity::builder()
+            .field("int", 1)
+            .field("big_int", BigInt::from(u64::MAX))
+            .field("int8", Value::Int8(-2))
+            .field("timestamp", Value::Timestamp(3))
+            .build();
+
+        #[derive(Deserialize)]
+        struct Integers {
+            int: u8,
+            big_int: u64,
+            int8: i64,
+            timestamp: i64,
+        }
+
+        let value = from_entity::<Integers>(entity).unwrap();
+        assert_eq!(value.int, 1);
+        assert_eq!(value.big_int, u64::MAX);
+        assert_eq!(value.int8, -2);
+        assert_eq!(value.timestamp, 3);
+    }
+
+    #[test]
+    fn big_numbers() {
+        #[derive(Deserialize, Serialize)]
+        struct Numbers {
+            int: BigInt,
+            decimal: BigDecimal,
+        }
+
+        let decimal = BigDecimal::from_parts(BigInt::from(-12345), BigInt::from(-2));
+        let entity = to_entity(&Numbers {
+            int: BigInt::from(i128::MIN),
+            decimal,
+        })
+        .unwrap();
+
+        let parts = |value: &BigDecimal| {
+            let (digits, exp) = value.to_parts();
+            (i128::try_from(&digits).ok(), i128::try_from(&exp).ok())
+        };
+        assert!(matches!(
+            &entity["int"],
+            Value::BigInt(int) if i128::try_from(int) == Ok(i128::MIN)
+        ));
+        assert!(matches!(
+            &entity["decimal"],
+            Value::BigDecimal(decimal) if parts(decimal) == (Some(-12345), Some(-2))
+        ));
+
+        let value = from_entity::<Numbers>(entity).unwrap();
+        assert_eq!(i128::try_from(&value.int), Ok(i128::MIN));
+        assert_eq!(parts(&value.decimal), (Some(-12345), Some(-2)));
+    }
+
+    #[test]
+    fn unit_enum_variants() {
+        let entity = Entity::builder().field("kind", "Mint").build();
+
+        #[derive(Debug, Deserialize)]
+        struct Value {
+            kind: Kind,
+        }
+
+        assert_eq!(from_entity::<Value>(entity).unwrap().kind, Kind::Mint);
+
+        let entity = Entity::builder().field("kind", "Unknown").build();
+        assert!(from_entity::<Value>(entity).is_err());
+
+        let entity = Entity::builder().field("kind", 1).build();
+        assert_eq!(
+            from_entity::<Value>(entity).unwrap_err().to_string(),
+            "invalid type: int, expected unit enum variant",
+        );
+    }
+
+    #[test]
+    fn serialize_errors() {
+        #[derive(Serialize)]
+        struct Inner {
+            value: i32,
+        }
+
+        #[derive(Serialize)]
+        struct Outer {
+            inner: Inner,
+        }
+
+        #[derive(Serialize)]
+        enum Variant {
+            Newtype(i32),
+        }
+
+        assert_eq!(
+            to_entity(&Outer {
+                inner: Inner { value: 1 }
+            })
+            .unwrap_err()
+            .to_string(),
+            "nested structs and maps are not supported in entities",
+        );
+        assert_eq!(
+            to_entity(
+                &[("inner", Variant::Newtype(1))]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+            )
+            .unwrap_err()
+            .to_string(),
+            "unsupported enum variant Variant::Newtype; only unit variants are supported",
+        );
+        assert_eq!(
+            to_entity(&1).unwrap_err().to_string(),
+            "expected struct or map, found int",
+        );
+        assert_eq!(
+            to_entity(&[(1, 2)].into_iter().collect::<BTreeMap<_, _>>())
+                .unwrap_err()
+                .to_string(),
+            "expected string entity field name, found int",
+        );
+        assert_eq!(
+            to_entity(
+                &[("value", f64::NAN)]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+            )
+            .unwrap_err()
+            .to_string(),
+            "non-finite decimal value NaN",
+        );
+    }
+}
@@ -1,14 +1,22 @@
 //! Ethereum, in all its glory.
 //!
-//! TODO(nlordell): Type-safe `FixedBytes`, and `Array` values.
 //! FIXME(nlordell): Make field access lazy - this avoids issues like with the
 //! `TransactionReceipt` type, where some fields are `null`.
 
+mod conv;
+mod filter;
+mod param;
+
+pub use self::{
+    filter::{BlockFilter, Schedule},
+    param::{ParamType, ParseParamTypeError},
+};
+pub use crate::conv::ValueError;
 use crate::{
     address::Address,
     crypto::Hash,
     ffi::{
-        boxed::{AscBox, AscRef},
+        boxed::{AscBox, AscRef, HostRef},
         eth::{
             AscBlock, AscCall, AscEthereumSmartContractCall, AscEvent, AscEventParam, AscLog,
             AscTransaction, AscTransactionReceipt,
@@ -25,13 +33,7 @@ use indexmap::IndexMap;
 /// Execute an Ethereum call.
 pub fn call(call: SmartContractCall) -> Option<Vec<Value>> {
     let call = call.to_raw();
-    let result = unsafe {
-        let result = sys::ethereum__call(call.as_ptr());
-        if result.is_null() {
-            return None;
-        }
-        &*result
-    };
+    let result = unsafe { HostRef::new_nullable(sys::ethereum__call(call.as_ptr()))?.into_ref() };
 
     Some(
         result
@@ -45,13 +47,7 @@ pub fn call(call: SmartContractCall) -> Option<Vec<Value>> {
 /// ABI-encode and Ethereum value.
 pub fn encode(value: &Value) -> Option<Vec<u8>> {
     let value = value.to_raw();
-    let data = unsafe {
-        let data = sys::ethereum__encode(value.as_ptr());
-        if data.is_null() {
-            return None;
-        }
-        &*data
-    };
+    let data = unsafe { HostRef::new_nullable(sys::ethereum__encode(value.as_ptr()))?.into_ref() };
     Some(data.as_slice().to_owned())
 }
 
@@ -60,15 +56,44 @@ pub fn decode(signature: impl AsRef<str>, data: impl AsRef<[u8]>) -> Option<Valu
     let signature = AscString::new(signature.as_ref());
     let data = AscBytes::from_bytes(data.as_ref());
     let value = unsafe {
-        let value = sys::ethereum__decode(signature.as_ptr(), data.as_ptr());
-        if value.is_null() {
-            return None;
-        }
-        &*value
+        HostRef::new_nullable(sys::ethereum__decode(signature.as_ptr(), data.as_ptr()))?.into_ref()
     };
     Some(Value::from_raw(value))
 }
 
+/// Returns the Ether balance of an address at the current block.
+///
+/// This requires the mapping to specify `apiVersion: 0.0.9` or later in the
+/// Subgraph manifest, as well as a Graph node version that provides the
+/// `ethereum.getBalance` host function. Note that, just like [`call`], this
+/// requires the data source's network to support Ethereum calls.
+///
+/// # Panics
+///
+/// Panics if the host does not return a balance.
+pub fn balance_of(address: &Address) -> BigInt {
+    let address = address.to_raw();
+    let balance = unsafe { HostRef::new_nullable(sys::ethereum__get_balance(address.as_ptr())) }
+        .expect("ethereum.getBalance returned null")
+        .into_ref();
+    BigInt::from_raw(balance)
+}
+
+/// Returns whether or not an address has contract code deployed to it at the
+/// current block.
+///
+/// The same manifest `apiVersion` requirements as [`balance_of`] apply.
+///
+/// # Panics
+///
+/// Panics if the host does not return a result.
+pub fn has_code(address: &Address) -> bool {
+    let address = address.to_raw();
+    let has_code = unsafe { HostRef::new_nullable(sys::ethereum__has_code(address.as_ptr())) }
+        .expect("ethereum.hasCode returned null");
+    **has_code
+}
+
 /// An Ethereum value.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Value {
@@ -118,6 +143,7 @@ impl Value {
                     .map(|value| Value::from_raw(value.as_asc_ref()))
                     .collect(),
             ),
+            AscEthereumValueData::Unknown(kind) => panic!("unknown Ethereum value kind {kind:#x}"),
         }
     }
 
@@ -152,11 +178,11 @@ impl Value {
         }
     }
 
-    /// Returns the Ethereum value as a fixed bytes, or `None` if the value the
-    /// wrong type.
-    pub fn as_fixed_bytes(&self) -> Option<&[u8]> {
+    /// Returns the Ethereum value as a fixed bytes array of length `N`, or
+    /// `None` if the value the wrong type or length.
+    pub fn as_fixed_bytes<const N: usize>(&self) -> Option<[u8; N]> {
         match self {
-            Self::FixedBytes(value) => Some(value),
+            Self::FixedBytes(value) => value.as_slice().try_into().ok(),
             _ => None,
         }
     }
@@ -232,6 +258,20 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Returns the Ethereum value as a vector of `T`, or an error if the value
+    /// is not a fixed array or array, or any of its items cannot be converted.
+    pub fn as_array_of<T>(&self) -> Result<Vec<T>, T::Error>
+    where
+        T: TryFrom<Value, Error = ValueError>,
+    {
+        Vec::try_from(self.clone())
+    }
+
+    /// Returns `true` if the value is of the specified ABI parameter type.
+    pub fn is_of_type(&self, kind: &ParamType) -> bool {
+        kind.matches(self)
+    }
 }
 
 /// A 256-byte bloom filter.
@@ -263,13 +303,13 @@ pub type BlockPtr = *const AscRef<AscBlock>;
 impl Block {
     fn from_raw(b: &'static AscRef<AscBlock>) -> Self {
         Self {
-            hash: b.hash().as_slice().try_into().unwrap(),
-            parent_hash: b.parent_hash().as_slice().try_into().unwrap(),
-            uncles_hash: b.uncles_hash().as_slice().try_into().unwrap(),
+            hash: Hash::from_raw(b.hash()),
+            parent_hash: Hash::from_raw(b.parent_hash()),
+            uncles_hash: Hash::from_raw(b.uncles_hash()),
             author: Address::from_raw(b.author()),
-            state_root: b.state_root().as_slice().try_into().unwrap(),
-            transactions_root: b.transactions_root().as_slice().try_into().unwrap(),
-            receipts_root: b.receipts_root().as_slice().try_into().unwrap(),
+            state_root: Hash::from_raw(b.state_root()),
+            transactions_root: Hash::from_raw(b.transactions_root()),
+            receipts_root: Hash::from_raw(b.receipts_root()),
             number: BigInt::from_raw(b.number()),
             gas_used: BigInt::from_raw(b.gas_used()),
             gas_limit: BigInt::from_raw(b.gas_limit()),
@@ -287,7 +327,7 @@ impl Block {
     ///
     /// This must be a pointer passed into a block handler.
     pub unsafe fn from_ptr(ptr: BlockPtr) -> Self {
-        Self::from_raw(&*ptr)
+        Self::from_raw(HostRef::new(ptr).into_ref())
     }
 }
 
@@ -308,7 +348,7 @@ pub struct Transaction {
 impl Transaction {
     fn from_raw(t: &'static AscRef<AscTransaction>) -> Self {
         Self {
-            hash: t.hash().as_slice().try_into().unwrap(),
+            hash: Hash::from_raw(t.hash()),
             index: BigInt::from_raw(t.index()),
             from: Address::from_raw(t.from()),
             to: t.to().map(Address::from_raw),
@@ -340,9 +380,9 @@ pub struct TransactionReceipt {
 impl TransactionReceipt {
     fn from_raw(t: &'static AscRef<AscTransactionReceipt>) -> Self {
         Self {
-            transaction_hash: t.transaction_hash().as_slice().try_into().unwrap(),
+            transaction_hash: Hash::from_raw(t.transaction_hash()),
             transaction_index: BigInt::from_raw(t.transaction_index()),
-            block_hash: t.block_hash().as_slice().try_into().unwrap(),
+            block_hash: Hash::from_raw(t.block_hash()),
             block_number: BigInt::from_raw(t.block_number()),
             cumulative_gas_used: BigInt::from_raw(t.cumulative_gas_used()),
             gas_used: BigInt::from_raw(t.gas_used()),
@@ -354,7 +394,7 @@ impl TransactionReceipt {
                 .map(|l| Log::from_raw(l.as_asc_ref()))
                 .collect(),
             status: BigInt::from_raw(t.status()),
-            root: t.root().as_slice().try_into().unwrap(),
+            root: Hash::from_raw(t.root()),
             logs_bloom: t.logs_bloom().as_slice().try_into().unwrap(),
         }
     }
@@ -384,12 +424,12 @@ impl Log {
                 .topics()
                 .as_slice()
                 .iter()
-                .map(|t| t.as_asc_ref().as_slice().try_into().unwrap())
+                .map(|t| Hash::from_raw(t.as_asc_ref()))
                 .collect(),
             data: l.data().as_slice().to_owned(),
-            block_hash: l.block_hash().as_slice().try_into().unwrap(),
+            block_hash: Hash::from_raw(l.block_hash()),
             block_number: BigInt::from_raw(l.block_number()),
-            transaction_hash: l.transaction_hash().as_slice().try_into().unwrap(),
+            transaction_hash: Hash::from_raw(l.transaction_hash()),
             transaction_index: BigInt::from_raw(l.transaction_index()),
             log_index: BigInt::from_raw(l.log_index()),
             transaction_log_index: BigInt::from_raw(l.transaction_log_index()),
@@ -431,10 +471,50 @@ impl Call {
     ///
     /// This must be a pointer passed into a call handler.
     pub unsafe fn from_ptr(ptr: CallPtr) -> Self {
-        Self::from_raw(&*ptr)
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+
+    /// Decodes the call's input values as a Rust type.
+    ///
+    /// The input values are converted positionally, as a tuple of the
+    /// function's parameters. This means that `T` can either be a tuple type or
+    /// a struct declared with [`eth_tuple!`](crate::eth_tuple).
+    pub fn decode_inputs<T>(&self) -> Result<T, ValueError>
+    where
+        T: TryFrom<Value, Error = ValueError>,
+    {
+        T::try_from(Value::Tuple(self.input_values.values().cloned().collect()))
+    }
+
+    /// Decodes the call's output values as a Rust type.
+    ///
+    /// See [`Call::decode_inputs`] for more details.
+    pub fn decode_outputs<T>(&self) -> Result<T, ValueError>
+    where
+        T: TryFrom<Value, Error = ValueError>,
+    {
+        T::try_from(Value::Tuple(self.output_values.values().cloned().collect()))
+    }
+
+    /// Returns `true` if the 4-byte selector of the call's transaction input
+    /// matches the specified function signature, for example
+    /// `transfer(address,uint256)`.
+    ///
+    /// Note that the host only provides the input of the outer transaction,
+    /// and not of the call itself. This means that for calls made from within
+    /// another contract, the selector of the outermost call is checked.
+    pub fn matches_signature(&self, signature: &str) -> bool {
+        self.transaction.input.get(..4) == Some(&selector(signature)[..])
     }
 }
 
+/// Computes the 4-byte function selector for a function signature, for example
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Hash::keccak(signature);
+    hash.as_bytes()[..4].try_into().unwrap()
+}
+
 /// Common representation for Ethereum smart contract events.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Event {
@@ -474,7 +554,24 @@ impl Event {
     ///
     /// This must be a pointer passed into a call handler.
     pub unsafe fn from_ptr(ptr: EventPtr) -> Self {
-        Self::from_raw(&*ptr)
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+
+    /// Returns a unique ID for the event, suitable for use as a `Bytes` entity
+    /// ID.
+    ///
+    /// The ID is the transaction hash concatenated with the little-endian
+    /// 32-bit log index, matching the `concatI32` ID pattern used by
+    /// AssemblyScript mappings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log index does not fit in an `i32`.
+    pub fn id(&self) -> Vec<u8> {
+        let log_index = i32::try_from(&self.log_index).expect("log index out of range");
+        let mut id = self.transaction.hash.as_bytes().to_vec();
+        id.extend_from_slice(&log_index.to_le_bytes());
+        id
     }
 
     /// Creates an event from a raw pointer, including the transaction receipt.
@@ -486,9 +583,10 @@ impl Event {
     /// This must be a pointer passed into a call handler and the transaction
     /// receipt is correctly initialized.
     pub unsafe fn from_ptr_with_receipt(ptr: EventPtr) -> Self {
+        let raw = HostRef::new(ptr).into_ref();
         Self {
-            receipt: (*ptr).receipt().map(TransactionReceipt::from_raw),
-            ..Self::from_raw(&*ptr)
+            receipt: raw.receipt().map(TransactionReceipt::from_raw),
+            ..Self::from_raw(raw)
         }
     }
 }
@@ -543,3 +641,101 @@ impl SmartContractCall<'_> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(inputs: Vec<Value>, outputs: Vec<Value>) -> Call {
+        let params = |values: Vec<Value>| {
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| (format!("param{i}"), value))
+                .collect()
+        };
+        Call {
+            to: Address::default(),
+            from: Address::default(),
+            block: Block {
+                hash: Hash::default(),
+                parent_hash: Hash::default(),
+                uncles_hash: Hash::default(),
+                author: Address::default(),
+                state_root: Hash::default(),
+                transactions_root: Hash::default(),
+                receipts_root: Hash::default(),
+                number: BigInt::default(),
+                gas_used: BigInt::default(),
+                gas_limit: BigInt::default(),
+                timestamp: BigInt::default(),
+                difficulty: BigInt::default(),
+                total_difficulty: BigInt::default(),
+                size: None,
+                base_fee_per_gas: None,
+            },
+            transaction: Transaction {
+                hash: Hash::default(),
+                index: BigInt::default(),
+                from: Address::default(),
+                to: None,
+                value: BigInt::default(),
+                gas_limit: BigInt::default(),
+                gas_price: BigInt::default(),
+                input: Vec::new(),
+                nonce: BigInt::default(),
+            },
+            input_values: params(inputs),
+            output_values: params(outputs),
+        }
+    }
+
+    crate::eth_tuple! {
+        struct Transfer {
+            to: Address,
+            value: u64,
+        }
+    }
+
+    #[test]
+    fn decode_inputs() {
+        let to = Address([0x42; 20]);
+        let call = call(
+            vec![Value::Address(to), Value::Uint(BigInt::from(1337_u64))],
+            vec![],
+        );
+
+        let (decoded_to, value) = call.decode_inputs::<(Address, u64)>().unwrap();
+        assert_eq!((decoded_to.0, value), (to.0, 1337));
+
+        let transfer = call.decode_inputs::<Transfer>().unwrap();
+        assert_eq!((transfer.to.0, transfer.value), (to.0, 1337));
+    }
+
+    #[test]
+    fn decode_outputs() {
+        let call = call(vec![], vec![Value::Bool(true), Value::String("ok".into())]);
+
+        assert_eq!(
+            call.decode_outputs::<(bool, String)>().unwrap(),
+            (true, "ok".to_owned()),
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        let call = call(vec![Value::Bool(true)], vec![]);
+
+        assert_eq!(
+            call.decode_inputs::<Transfer>().err(),
+            Some(ValueError::Length {
+                expected: 2,
+                found: 1
+            }),
+        );
+        assert_eq!(
+            call.decode_inputs::<(u64,)>().unwrap_err(),
+            ValueError::unexpected("integer", "bool"),
+        );
+    }
+}
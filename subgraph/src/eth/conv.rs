@@ -0,0 +1,225 @@
+//! Conversions from dynamic Ethereum values into Rust types.
+
+use super::Value;
+use crate::{address::Address, conv::ValueError, entity, num::BigInt};
+
+impl Value {
+    /// Returns a human readable name of the kind of Ethereum value.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Address(_) => "address",
+            Self::FixedBytes(_) => "fixed bytes",
+            Self::Bytes(_) => "bytes",
+            Self::Int(_) => "int",
+            Self::Uint(_) => "uint",
+            Self::Bool(_) => "bool",
+            Self::String(_) => "string",
+            Self::FixedArray(_) => "fixed array",
+            Self::Array(_) => "array",
+            Self::Tuple(_) => "tuple",
+        }
+    }
+}
+
+impl TryFrom<Value> for Address {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Address(value) => Ok(value),
+            value => Err(ValueError::unexpected("address", value.kind())),
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<Value> for [u8; N] {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::FixedBytes(value) => {
+                let found = value.len();
+                value
+                    .try_into()
+                    .map_err(|_| ValueError::Length { expected: N, found })
+            }
+            value => Err(ValueError::unexpected("fixed bytes", value.kind())),
+        }
+    }
+}
+
+impl TryFrom<Value> for BigInt {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Int(value) | Value::Uint(value) => Ok(value),
+            value => Err(ValueError::unexpected("integer", value.kind())),
+        }
+    }
+}
+
+macro_rules! impl_try_from_value_for_int {
+    ($($t:ty),*) => {$(
+        impl TryFrom<Value> for $t {
+            type Error = ValueError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                let value = BigInt::try_from(value)?;
+                Self::try_from(&value).map_err(|_| ValueError::Range {
+                    target: stringify!($t),
+                })
+            }
+        }
+    )*};
+}
+
+impl_try_from_value_for_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+impl TryFrom<Value> for bool {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bool(value) => Ok(value),
+            value => Err(ValueError::unexpected("bool", value.kind())),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(value) => Ok(value),
+            value => Err(ValueError::unexpected("string", value.kind())),
+        }
+    }
+}
+
+/// Converts array values item by item.
+///
+/// Note that `bytes` values are not arrays, and convert into
+/// [`Bytes`](crate::bytes::Bytes) instead of `Vec<u8>`.
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value, Error = ValueError>,
+{
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::FixedArray(items) | Value::Array(items) => {
+                items.into_iter().map(T::try_from).collect()
+            }
+            value => Err(ValueError::unexpected("array", value.kind())),
+        }
+    }
+}
+
+macro_rules! impl_try_from_value_for_tuple {
+    ($($len:literal => ($($t:ident),*);)*) => {$(
+        impl<$($t),*> TryFrom<Value> for ($($t,)*)
+        where
+            $($t: TryFrom<Value, Error = ValueError>,)*
+        {
+            type Error = ValueError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                let items = match value {
+                    Value::Tuple(items) => items,
+                    value => return Err(ValueError::unexpected("tuple", value.kind())),
+                };
+                if items.len() != $len {
+                    return Err(ValueError::Length {
+                        expected: $len,
+                        found: items.len(),
+                    });
+                }
+
+                let mut items = items.into_iter();
+                Ok(($($t::try_from(items.next().unwrap())?,)*))
+            }
+        }
+    )*};
+}
+
+impl_try_from_value_for_tuple! {
+    1 => (A);
+    2 => (A, B);
+    3 => (A, B, C);
+    4 => (A, B, C, D);
+    5 => (A, B, C, D, E);
+    6 => (A, B, C, D, E, F);
+    7 => (A, B, C, D, E, F, G);
+    8 => (A, B, C, D, E, F, G, H);
+    9 => (A, B, C, D, E, F, G, H, I);
+    10 => (A, B, C, D, E, F, G, H, I, J);
+    11 => (A, B, C, D, E, F, G, H, I, J, K);
+    12 => (A, B, C, D, E, F, G, H, I, J, K, L);
+}
+
+impl TryFrom<Value> for entity::Value {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Address(value) => Ok(Self::Bytes(value.0.to_vec())),
+            Value::FixedBytes(value) | Value::Bytes(value) => Ok(Self::Bytes(value)),
+            Value::Int(value) | Value::Uint(value) => Ok(Self::BigInt(value)),
+            Value::Bool(value) => Ok(Self::Bool(value)),
+            Value::String(value) => Ok(Self::String(value)),
+            Value::FixedArray(value) | Value::Array(value) => Ok(Self::Array(
+                value
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            value => Err(ValueError::unexpected("entity compatible", value.kind())),
+        }
+    }
+}
+
+/// Declares a struct that converts from an Ethereum tuple value.
+///
+/// Fields are converted positionally, in declaration order, making these
+/// structs suitable for decoding tuple parameters as well as call inputs and
+/// outputs with [`Call::decode_inputs`](crate::eth::Call::decode_inputs).
+/// Structs can have at most 12 fields.
+///
+/// ```ignore
+/// subgraph::eth_tuple! {
+///     #[derive(Clone, Debug)]
+///     pub struct Transfer {
+///         pub to: Address,
+///         pub value: BigInt,
+///     }
+/// }
+///
+/// let Transfer { to, value } = call.decode_inputs()?;
+/// ```
+#[macro_export]
+macro_rules! eth_tuple {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $ty,)*
+        }
+
+        impl ::std::convert::TryFrom<$crate::eth::Value> for $name {
+            type Error = $crate::eth::ValueError;
+
+            fn try_from(value: $crate::eth::Value) -> ::std::result::Result<Self, Self::Error> {
+                let ($($field,)*) =
+                    <($($ty,)*) as ::std::convert::TryFrom<$crate::eth::Value>>::try_from(value)?;
+                ::std::result::Result::Ok(Self { $($field,)* })
+            }
+        }
+    };
+}
@@ -0,0 +1,265 @@
+//! Ethereum block handler filters.
+
+use super::Block;
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::FusedIterator,
+    ops::RangeInclusive,
+};
+
+/// A block handler filter, as specified by a `blockHandlers` entry in the
+/// Subgraph manifest.
+///
+/// The host does not tell a block handler which filter triggered it. Instead,
+/// each filter should get its own handler, which can use its filter to reason
+/// about the blocks it is called for. Block handlers without a filter are
+/// called for every block, which is equivalent to polling every block.
+///
+/// ```ignore
+/// // blockHandlers:
+/// //   - handler: initialize
+/// //     filter: { kind: once }
+/// #[no_mangle]
+/// pub unsafe extern "C" fn initialize(block: eth::BlockPtr) {
+///     let block = eth::Block::from_ptr(block);
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlockFilter {
+    /// The handler is called for blocks containing a call to the data source
+    /// contract.
+    Call,
+    /// The handler is called every `every` blocks, starting with the data
+    /// source's start block.
+    Polling { every: u64 },
+    /// The handler is called once, for the data source's start block. This is
+    /// typically used for initialization.
+    Once,
+}
+
+impl BlockFilter {
+    /// Returns the filter kind, as specified in the Subgraph manifest.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Call => "call",
+            Self::Polling { .. } => "polling",
+            Self::Once => "once",
+        }
+    }
+
+    /// Returns `true` if the filter schedules the handler for the specified
+    /// block number, for a data source with the specified start block.
+    ///
+    /// Call filters depend on the transactions in a block rather than its
+    /// number, so they never schedule any blocks. Polling filters with an
+    /// interval of `0` are rejected by the host, and never schedule any blocks
+    /// either.
+    pub fn is_scheduled(&self, start_block: u64, number: u64) -> bool {
+        match self {
+            Self::Call => false,
+            Self::Polling { every } => {
+                *every > 0 && number >= start_block && (number - start_block) % *every == 0
+            }
+            Self::Once => number == start_block,
+        }
+    }
+
+    /// Returns `true` if the filter schedules the handler for the specified
+    /// block, for a data source with the specified start block.
+    ///
+    /// See [`BlockFilter::is_scheduled`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block number does not fit in a `u64`.
+    pub fn is_scheduled_block(&self, start_block: u64, block: &Block) -> bool {
+        let number = u64::try_from(&block.number).expect("block number out of range");
+        self.is_scheduled(start_block, number)
+    }
+
+    /// Simulates the schedule of the filter over a range of block numbers,
+    /// returning the block numbers that the handler would be called for in
+    /// ascending order.
+    ///
+    /// This is useful for testing polling and initialization handlers without
+    /// a Graph node.
+    pub fn schedule(&self, start_block: u64, blocks: RangeInclusive<u64>) -> Schedule {
+        let (first, last) = blocks.into_inner();
+        let (next, step) = match *self {
+            Self::Call | Self::Polling { every: 0 } => (None, 1),
+            Self::Polling { every } => {
+                let first = first.max(start_block);
+                let offset = (every - (first - start_block) % every) % every;
+                (first.checked_add(offset), every)
+            }
+            Self::Once => {
+                let scheduled = (first..=last).contains(&start_block).then_some(start_block);
+                return Schedule {
+                    next: scheduled,
+                    last: start_block,
+                    step: 1,
+                };
+            }
+        };
+        Schedule {
+            next: next.filter(|next| *next <= last),
+            last,
+            step,
+        }
+    }
+}
+
+impl Display for BlockFilter {
+    /// Formats the filter as a YAML flow mapping for the Subgraph manifest.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Polling { every } => write!(f, "{{ kind: polling, every: {every} }}"),
+            _ => write!(f, "{{ kind: {} }}", self.kind()),
+        }
+    }
+}
+
+/// An iterator over the block numbers scheduled by a [`BlockFilter`].
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    next: Option<u64>,
+    last: u64,
+    step: u64,
+}
+
+impl Iterator for Schedule {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current
+            .checked_add(self.step)
+            .filter(|next| *next <= self.last);
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.next {
+            Some(next) => ((self.last - next) / self.step)
+                .checked_add(1)
+                .and_then(|len| usize::try_from(len).ok()),
+            None => Some(0),
+        };
+        match len {
+            Some(len) => (len, Some(len)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl FusedIterator for Schedule {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(filter: BlockFilter, start_block: u64, blocks: RangeInclusive<u64>) -> Vec<u64> {
+        let schedule = filter.schedule(start_block, blocks.clone());
+        let size_hint = schedule.size_hint();
+        let numbers = schedule.collect::<Vec<_>>();
+        assert_eq!(size_hint, (numbers.len(), Some(numbers.len())));
+        for number in blocks {
+            assert_eq!(
+                filter.is_scheduled(start_block, number),
+                numbers.contains(&number),
+                "{filter} block {number}",
+            );
+        }
+        numbers
+    }
+
+    #[test]
+    fn once() {
+        let once = BlockFilter::Once;
+        assert_eq!(schedule(once, 10, 0..=20), [10]);
+        assert_eq!(schedule(once, 10, 10..=10), [10]);
+        assert_eq!(schedule(once, 10, 0..=9), []);
+        assert_eq!(schedule(once, 10, 11..=20), []);
+    }
+
+    #[test]
+    fn polling() {
+        let polling = BlockFilter::Polling { every: 3 };
+        assert_eq!(schedule(polling, 10, 0..=20), [10, 13, 16, 19]);
+        assert_eq!(schedule(polling, 10, 14..=22), [16, 19, 22]);
+        assert_eq!(schedule(polling, 10, 16..=16), [16]);
+        assert_eq!(schedule(polling, 10, 17..=18), []);
+
+        let every_block = BlockFilter::Polling { every: 1 };
+        assert_eq!(schedule(every_block, 5, 3..=8), [5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn polling_before_start_block() {
+        // Block 7 is a multiple of 3 blocks away from the start block, but is
+        // before it.
+        let polling = BlockFilter::Polling { every: 3 };
+        assert_eq!(schedule(polling, 10, 0..=9), []);
+        assert!(!polling.is_scheduled(10, 7));
+        assert!(!polling.is_scheduled(10, 1));
+    }
+
+    #[test]
+    fn polling_every_zero() {
+        let polling = BlockFilter::Polling { every: 0 };
+        assert_eq!(schedule(polling, 10, 0..=20), []);
+        assert!(!polling.is_scheduled(10, 10));
+    }
+
+    #[test]
+    fn call() {
+        assert_eq!(schedule(BlockFilter::Call, 10, 0..=20), []);
+    }
+
+    #[test]
+    fn schedule_does_not_overflow() {
+        let polling = BlockFilter::Polling { every: 2 };
+        let numbers = polling
+            .schedule(u64::MAX - 4, u64::MAX - 4..=u64::MAX)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, [u64::MAX - 4, u64::MAX - 2, u64::MAX]);
+
+        let polling = BlockFilter::Polling { every: u64::MAX };
+        let numbers = polling.schedule(1, 0..=u64::MAX).collect::<Vec<_>>();
+        assert_eq!(numbers, [1]);
+    }
+
+    #[test]
+    fn schedule_full_range() {
+        let every_block = BlockFilter::Polling { every: 1 };
+        let mut schedule = every_block.schedule(0, 0..=u64::MAX);
+        assert_eq!(schedule.size_hint(), (usize::MAX, None));
+        assert_eq!(schedule.next(), Some(0));
+        assert_eq!(
+            schedule.size_hint(),
+            match usize::try_from(u64::MAX) {
+                Ok(len) => (len, Some(len)),
+                Err(_) => (usize::MAX, None),
+            },
+        );
+        assert_eq!(schedule.take(3).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut schedule = every_block.schedule(0, u64::MAX - 1..=u64::MAX);
+        assert_eq!(schedule.size_hint(), (2, Some(2)));
+        assert_eq!(schedule.next(), Some(u64::MAX - 1));
+        assert_eq!(schedule.next(), Some(u64::MAX));
+        assert_eq!(schedule.size_hint(), (0, Some(0)));
+        assert_eq!(schedule.next(), None);
+    }
+
+    #[test]
+    fn manifest_representation() {
+        assert_eq!(BlockFilter::Call.to_string(), "{ kind: call }");
+        assert_eq!(
+            BlockFilter::Polling { every: 5 }.to_string(),
+            "{ kind: polling, every: 5 }",
+        );
+        assert_eq!(BlockFilter::Once.to_string(), "{ kind: once }");
+    }
+}
@@ -0,0 +1,312 @@
+//! Ethereum ABI parameter types.
+
+use super::Value;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// An Ethereum ABI parameter type.
+///
+/// Parameter types can be parsed from their ABI signature representation (for
+/// example `uint256` or `(address,bytes32)[]`) and are formatted in their
+/// canonical form, making them suitable for computing function selectors.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParamType {
+    Address,
+    FixedBytes(usize),
+    Bytes,
+    Int(usize),
+    Uint(usize),
+    Bool,
+    String,
+    FixedArray(Box<ParamType>, usize),
+    Array(Box<ParamType>),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Returns `true` if the Ethereum value is of this parameter type.
+    ///
+    /// Note that integer values are not checked against the bit-width of the
+    /// parameter type.
+    pub fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Address, Value::Address(_)) => true,
+            (Self::FixedBytes(len), Value::FixedBytes(value)) => value.len() == *len,
+            (Self::Bytes, Value::Bytes(_)) => true,
+            (Self::Int(_), Value::Int(_)) => true,
+            (Self::Uint(_), Value::Uint(_)) => true,
+            (Self::Bool, Value::Bool(_)) => true,
+            (Self::String, Value::String(_)) => true,
+            (Self::FixedArray(kind, len), Value::FixedArray(items)) => {
+                items.len() == *len && items.iter().all(|item| kind.matches(item))
+            }
+            (Self::Array(kind), Value::Array(items)) => items.iter().all(|item| kind.matches(item)),
+            (Self::Tuple(kinds), Value::Tuple(items)) => {
+                kinds.len() == items.len()
+                    && kinds
+                        .iter()
+                        .zip(items)
+                        .all(|(kind, item)| kind.matches(item))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for ParamType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Address => f.write_str("address"),
+            Self::FixedBytes(len) => write!(f, "bytes{len}"),
+            Self::Bytes => f.write_str("bytes"),
+            Self::Int(bits) => write!(f, "int{bits}"),
+            Self::Uint(bits) => write!(f, "uint{bits}"),
+            Self::Bool => f.write_str("bool"),
+            Self::String => f.write_str("string"),
+            Self::FixedArray(kind, len) => write!(f, "{kind}[{len}]"),
+            Self::Array(kind) => write!(f, "{kind}[]"),
+            Self::Tuple(kinds) => {
+                f.write_str("(")?;
+                for (i, kind) in kinds.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{kind}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = ParseParamTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseParamTypeError(s.to_owned());
+
+        let (kind, rest) = parse(s).ok_or_else(err)?;
+        if !rest.is_empty() {
+            return Err(err());
+        }
+        Ok(kind)
+    }
+}
+
+/// Parses a parameter type from the start of a string, returning the parsed
+/// type along with the remaining unparsed input.
+fn parse(s: &str) -> Option<(ParamType, &str)> {
+    let (mut kind, mut rest) = match s.strip_prefix('(') {
+        Some(mut rest) => {
+            let mut kinds = Vec::new();
+            if let Some(tail) = rest.strip_prefix(')') {
+                rest = tail;
+            } else {
+                loop {
+                    let (kind, tail) = parse(rest)?;
+                    kinds.push(kind);
+                    match tail.as_bytes().first()? {
+                        b',' => rest = &tail[1..],
+                        b')' => {
+                            rest = &tail[1..];
+                            break;
+                        }
+                        _ => return None,
+                    }
+                }
+            }
+            (ParamType::Tuple(kinds), rest)
+        }
+        None => {
+            let end = s
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(s.len());
+            let (name, rest) = s.split_at(end);
+            (elementary(name)?, rest)
+        }
+    };
+
+    while let Some(tail) = rest.strip_prefix('[') {
+        let end = tail.find(']')?;
+        let (len, tail) = tail.split_at(end);
+        kind = match len {
+            "" => ParamType::Array(Box::new(kind)),
+            len => ParamType::FixedArray(Box::new(kind), parse_size(len)?),
+        };
+        rest = &tail[1..];
+    }
+
+    Some((kind, rest))
+}
+
+/// Parses an elementary (i.e. non-tuple and non-array) parameter type.
+fn elementary(name: &str) -> Option<ParamType> {
+    let kind = match name {
+        "address" => ParamType::Address,
+        "bytes" => ParamType::Bytes,
+        "bool" => ParamType::Bool,
+        "string" => ParamType::String,
+        "int" => ParamType::Int(256),
+        "uint" => ParamType::Uint(256),
+        _ => {
+            if let Some(len) = name.strip_prefix("bytes") {
+                match parse_size(len)? {
+                    len @ 1..=32 => ParamType::FixedBytes(len),
+                    _ => return None,
+                }
+            } else if let Some(bits) = name.strip_prefix("uint") {
+                ParamType::Uint(bits_size(bits)?)
+            } else if let Some(bits) = name.strip_prefix("int") {
+                ParamType::Int(bits_size(bits)?)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(kind)
+}
+
+/// Parses a decimal size, rejecting leading `0`s.
+fn parse_size(s: &str) -> Option<usize> {
+    if s.starts_with('0') || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parses an integer bit-width.
+fn bits_size(s: &str) -> Option<usize> {
+    match parse_size(s)? {
+        bits @ 8..=256 if bits % 8 == 0 => Some(bits),
+        _ => None,
+    }
+}
+
+/// An error parsing an Ethereum ABI parameter type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseParamTypeError(String);
+
+impl Display for ParseParamTypeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "invalid ABI parameter type {:?}", self.0)
+    }
+}
+
+impl Error for ParseParamTypeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<ParamType, ParseParamTypeError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parses_elementary_types() {
+        assert_eq!(parse("address").unwrap(), ParamType::Address);
+        assert_eq!(parse("bool").unwrap(), ParamType::Bool);
+        assert_eq!(parse("string").unwrap(), ParamType::String);
+        assert_eq!(parse("bytes").unwrap(), ParamType::Bytes);
+        assert_eq!(parse("bytes1").unwrap(), ParamType::FixedBytes(1));
+        assert_eq!(parse("bytes32").unwrap(), ParamType::FixedBytes(32));
+        assert_eq!(parse("uint").unwrap(), ParamType::Uint(256));
+        assert_eq!(parse("int").unwrap(), ParamType::Int(256));
+        assert_eq!(parse("uint8").unwrap(), ParamType::Uint(8));
+        assert_eq!(parse("int248").unwrap(), ParamType::Int(248));
+    }
+
+    #[test]
+    fn parses_tuples() {
+        assert_eq!(parse("()").unwrap(), ParamType::Tuple(vec![]));
+        assert_eq!(
+            parse("(address,(uint256,bytes),bool)").unwrap(),
+            ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bytes]),
+                ParamType::Bool,
+            ]),
+        );
+    }
+
+    #[test]
+    fn parses_nested_arrays() {
+        assert_eq!(
+            parse("uint256[2][]").unwrap(),
+            ParamType::Array(Box::new(ParamType::FixedArray(
+                Box::new(ParamType::Uint(256)),
+                2,
+            ))),
+        );
+        assert_eq!(
+            parse("(address,bytes32)[][3]").unwrap(),
+            ParamType::FixedArray(
+                Box::new(ParamType::Array(Box::new(ParamType::Tuple(vec![
+                    ParamType::Address,
+                    ParamType::FixedBytes(32),
+                ])))),
+                3,
+            ),
+        );
+    }
+
+    #[test]
+    fn formats_canonical_representation() {
+        for s in [
+            "address",
+            "bytes4",
+            "int256",
+            "uint8[]",
+            "(address,(uint256,bytes)[2],bool)[]",
+            "()",
+        ] {
+            assert_eq!(parse(s).unwrap().to_string(), s);
+        }
+        assert_eq!(parse("uint").unwrap().to_string(), "uint256");
+    }
+
+    #[test]
+    fn rejects_malformed_types() {
+        for s in [
+            "",
+            "foo",
+            "uint7",
+            "uint0",
+            "uint264",
+            "uint08",
+            "int 256",
+            "bytes0",
+            "bytes33",
+            "bytes01",
+            "uint256[",
+            "uint256[01]",
+            "uint256[x]",
+            "uint256]",
+            "(uint256",
+            "(uint256,)",
+            "(,uint256)",
+            "(uint256))",
+            "(uint256;bool)",
+        ] {
+            assert!(parse(s).is_err(), "{s:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn matches_values() {
+        let kind = parse("(bytes2,uint8[2],bool[])").unwrap();
+        let value = |len| {
+            Value::Tuple(vec![
+                Value::FixedBytes(vec![0; len]),
+                Value::FixedArray(vec![Value::Uint(1.into()), Value::Uint(2.into())]),
+                Value::Array(vec![Value::Bool(true)]),
+            ])
+        };
+        assert!(kind.matches(&value(2)));
+        assert!(!kind.matches(&value(3)));
+        assert!(!kind.matches(&Value::Bool(true)));
+    }
+}
@@ -6,17 +6,15 @@
 
 #![doc(hidden)]
 
-use crate::ffi::{
-    boxed::{ALIGN, TYPE_ID},
-    str::AscString,
-    sys,
-};
+use crate::ffi::{boxed::ALIGN, class, str::AscString, sys};
 use std::{
     alloc::{self, Layout},
     panic, ptr,
 };
 
-#[export_name = "_start"]
+// NOTE: Only export `_start` for WASM targets, as it would otherwise collide
+// with the C runtime entry point when linking native test binaries.
+#[cfg_attr(target_arch = "wasm32", export_name = "_start")]
 pub extern "C" fn start() {
     panic::set_hook(Box::new(|info| {
         let message = info
@@ -39,8 +37,6 @@ pub extern "C" fn start() {
     // TODO(nlordell):
     // #[cfg(feature = "log")]
     // install_subgraph_logger();
-    // #[cfg(feature = "allocator")]
-    // install_custom_allocator();
 }
 
 #[export_name = "allocate"]
@@ -59,6 +55,6 @@ pub extern "C" fn allocate(size: usize) -> *mut u8 {
 }
 
 #[export_name = "id_of_type"]
-pub extern "C" fn id_of_type(_type_index: u32) -> u32 {
-    TYPE_ID
+pub extern "C" fn id_of_type(type_index: u32) -> u32 {
+    class::class_id(type_index)
 }
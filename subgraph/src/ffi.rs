@@ -1,13 +1,20 @@
 //! Module implemting FFI bindings for interacting with the Subgraph host.
 //!
+//! Pointers returned by host functions and passed into handlers are checked in
+//! debug builds when converting them into [`boxed::HostRef`]s.
+//!
 //! FIXME(nlordell): In many places, we are working with references where they
 //! should be pointers because of possible UB (see issue with `null` fields in
-//! `AscTransactionReceipt` type). We need to add checks everywhere when
-//! receiving values from the host on alignment and non-null-ness.
+//! `AscTransactionReceipt` type). Only the pointers returned by host functions
+//! and passed into handlers are checked, and not the pointers nested in values
+//! provided by the host.
 
+pub mod arweave;
 pub mod boxed;
 pub mod buf;
+pub mod class;
 pub mod eth;
+pub mod near;
 pub mod num;
 pub mod str;
 pub mod sys;
@@ -0,0 +1,241 @@
+//! Arweave-specific type definitions.
+//!
+//! The layouts match the AssemblyScript classes that the host generates for
+//! Arweave block and transaction handlers.
+
+use super::{
+    boxed::{AscBox, AscRef},
+    str::{AscStr, AscString},
+    types::AscBytes,
+    value::AscArray,
+};
+
+/// An Arweave block.
+#[repr(C)]
+pub struct AscBlock {
+    timestamp: u64,
+    last_retarget: u64,
+    height: u64,
+    indep_hash: AscBox<AscBytes>,
+    nonce: AscBox<AscBytes>,
+    previous_block: AscBox<AscBytes>,
+    diff: AscBox<AscBytes>,
+    hash: AscBox<AscBytes>,
+    tx_root: AscBox<AscBytes>,
+    txs: AscBox<AscArray<AscBox<AscBytes>>>,
+    wallet_list: AscBox<AscBytes>,
+    reward_addr: AscBox<AscBytes>,
+    tags: AscBox<AscArray<AscBox<AscTag>>>,
+    reward_pool: AscBox<AscBytes>,
+    weave_size: AscBox<AscBytes>,
+    block_size: AscBox<AscBytes>,
+    cumulative_diff: AscBox<AscBytes>,
+    hash_list_merkle: AscBox<AscBytes>,
+    poa: AscBox<AscProofOfAccess>,
+}
+
+impl AscBlock {
+    pub(crate) fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub(crate) fn last_retarget(&self) -> u64 {
+        self.last_retarget
+    }
+
+    pub(crate) fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub(crate) fn indep_hash(&self) -> &AscRef<AscBytes> {
+        self.indep_hash.as_asc_ref()
+    }
+
+    pub(crate) fn nonce(&self) -> &AscRef<AscBytes> {
+        self.nonce.as_asc_ref()
+    }
+
+    pub(crate) fn previous_block(&self) -> &AscRef<AscBytes> {
+        self.previous_block.as_asc_ref()
+    }
+
+    pub(crate) fn diff(&self) -> &AscRef<AscBytes> {
+        self.diff.as_asc_ref()
+    }
+
+    pub(crate) fn hash(&self) -> &AscRef<AscBytes> {
+        self.hash.as_asc_ref()
+    }
+
+    pub(crate) fn tx_root(&self) -> &AscRef<AscBytes> {
+        self.tx_root.as_asc_ref()
+    }
+
+    pub(crate) fn txs(&self) -> &AscRef<AscArray<AscBox<AscBytes>>> {
+        self.txs.as_asc_ref()
+    }
+
+    pub(crate) fn wallet_list(&self) -> &AscRef<AscBytes> {
+        self.wallet_list.as_asc_ref()
+    }
+
+    pub(crate) fn reward_addr(&self) -> &AscRef<AscBytes> {
+        self.reward_addr.as_asc_ref()
+    }
+
+    pub(crate) fn tags(&self) -> &AscRef<AscArray<AscBox<AscTag>>> {
+        self.tags.as_asc_ref()
+    }
+
+    pub(crate) fn reward_pool(&self) -> &AscRef<AscBytes> {
+        self.reward_pool.as_asc_ref()
+    }
+
+    pub(crate) fn weave_size(&self) -> &AscRef<AscBytes> {
+        self.weave_size.as_asc_ref()
+    }
+
+    pub(crate) fn block_size(&self) -> &AscRef<AscBytes> {
+        self.block_size.as_asc_ref()
+    }
+
+    pub(crate) fn cumulative_diff(&self) -> &AscRef<AscBytes> {
+        self.cumulative_diff.as_asc_ref()
+    }
+
+    pub(crate) fn hash_list_merkle(&self) -> &AscRef<AscBytes> {
+        self.hash_list_merkle.as_asc_ref()
+    }
+
+    pub(crate) fn poa(&self) -> &AscRef<AscProofOfAccess> {
+        self.poa.as_asc_ref()
+    }
+}
+
+/// An Arweave proof of access.
+#[repr(C)]
+pub struct AscProofOfAccess {
+    option: AscString,
+    tx_path: AscBox<AscBytes>,
+    data_path: AscBox<AscBytes>,
+    chunk: AscBox<AscBytes>,
+}
+
+impl AscProofOfAccess {
+    pub(crate) fn option(&self) -> &AscStr {
+        self.option.as_asc_str()
+    }
+
+    pub(crate) fn tx_path(&self) -> &AscRef<AscBytes> {
+        self.tx_path.as_asc_ref()
+    }
+
+    pub(crate) fn data_path(&self) -> &AscRef<AscBytes> {
+        self.data_path.as_asc_ref()
+    }
+
+    pub(crate) fn chunk(&self) -> &AscRef<AscBytes> {
+        self.chunk.as_asc_ref()
+    }
+}
+
+/// An Arweave transaction.
+#[repr(C)]
+pub struct AscTransaction {
+    format: u32,
+    id: AscBox<AscBytes>,
+    last_tx: AscBox<AscBytes>,
+    owner: AscBox<AscBytes>,
+    tags: AscBox<AscArray<AscBox<AscTag>>>,
+    target: AscBox<AscBytes>,
+    quantity: AscBox<AscBytes>,
+    data: AscBox<AscBytes>,
+    data_size: AscBox<AscBytes>,
+    data_root: AscBox<AscBytes>,
+    signature: AscBox<AscBytes>,
+    reward: AscBox<AscBytes>,
+}
+
+impl AscTransaction {
+    pub(crate) fn format(&self) -> u32 {
+        self.format
+    }
+
+    pub(crate) fn id(&self) -> &AscRef<AscBytes> {
+        self.id.as_asc_ref()
+    }
+
+    pub(crate) fn last_tx(&self) -> &AscRef<AscBytes> {
+        self.last_tx.as_asc_ref()
+    }
+
+    pub(crate) fn owner(&self) -> &AscRef<AscBytes> {
+        self.owner.as_asc_ref()
+    }
+
+    pub(crate) fn tags(&self) -> &AscRef<AscArray<AscBox<AscTag>>> {
+        self.tags.as_asc_ref()
+    }
+
+    pub(crate) fn target(&self) -> &AscRef<AscBytes> {
+        self.target.as_asc_ref()
+    }
+
+    pub(crate) fn quantity(&self) -> &AscRef<AscBytes> {
+        self.quantity.as_asc_ref()
+    }
+
+    pub(crate) fn data(&self) -> &AscRef<AscBytes> {
+        self.data.as_asc_ref()
+    }
+
+    pub(crate) fn data_size(&self) -> &AscRef<AscBytes> {
+        self.data_size.as_asc_ref()
+    }
+
+    pub(crate) fn data_root(&self) -> &AscRef<AscBytes> {
+        self.data_root.as_asc_ref()
+    }
+
+    pub(crate) fn signature(&self) -> &AscRef<AscBytes> {
+        self.signature.as_asc_ref()
+    }
+
+    pub(crate) fn reward(&self) -> &AscRef<AscBytes> {
+        self.reward.as_asc_ref()
+    }
+}
+
+/// An Arweave block or transaction tag.
+#[repr(C)]
+pub struct AscTag {
+    name: AscBox<AscBytes>,
+    value: AscBox<AscBytes>,
+}
+
+impl AscTag {
+    pub(crate) fn name(&self) -> &AscRef<AscBytes> {
+        self.name.as_asc_ref()
+    }
+
+    pub(crate) fn value(&self) -> &AscRef<AscBytes> {
+        self.value.as_asc_ref()
+    }
+}
+
+/// An Arweave transaction along with its block.
+#[repr(C)]
+pub struct AscTransactionWithBlock {
+    tx: AscBox<AscTransaction>,
+    block: AscBox<AscBlock>,
+}
+
+impl AscTransactionWithBlock {
+    pub(crate) fn tx(&self) -> &AscRef<AscTransaction> {
+        self.tx.as_asc_ref()
+    }
+
+    pub(crate) fn block(&self) -> &AscRef<AscBlock> {
+        self.block.as_asc_ref()
+    }
+}
@@ -1,16 +1,17 @@
 //! AssemblyScript object boxing.
 //!
-//! FIXME(nlordell):
-//! - Move to using reference-counted objects, this would allow allocation and
-//!   deallocation to work in `cargo test`.
-//! - Make sure pointers received from the host are well-alligned and non-null.
-//! - Differenciate between host provided pointers, and internal allocations
-//!   for a safer FFI interface.
+//! Boxed values are reference-counted, so cloning a box shares the underlying
+//! object instead of copying it. Pointers returned by host functions are
+//! wrapped in [`HostRef`]s instead, which are checked in debug builds when they
+//! are created.
 
+use super::class::{self, AscClass};
 use std::{
     alloc::{self, Layout},
+    any,
     borrow::{Borrow, Cow},
-    fmt::{self, Debug, Formatter},
+    cell::Cell,
+    fmt::{self, Debug, Display, Formatter},
     iter::FromIterator,
     mem,
     ops::Deref,
@@ -18,13 +19,6 @@ use std::{
     slice,
 };
 
-/// FIXME(nlordell): We currently don't use AssemblyScript type IDs at all
-/// internally in the module. Additionally, the Subgraph host only uses these to
-/// be compatible with the AssemblyScript runtime, which isn't a concern for us.
-/// For completeness, this should be implemented in the future in case the host
-/// starts requiring these values to be correct.
-pub const TYPE_ID: u32 = 0;
-
 /// The default alignment to use for AssemblyScript allocations.
 ///
 /// Note that we **over-align** things. This just makes our life easier in
@@ -80,11 +74,14 @@ where
     type Ref = AscSlice<T>;
 }
 
-impl<T> AscBox<T> {
+impl<T> AscBox<T>
+where
+    T: AscClass,
+{
     /// Creates a new boxed AssemblyScript value.
     pub fn new(value: T) -> Self {
         let data = unsafe {
-            let data = alloc_box::<T>(TYPE_ID, 1);
+            let data = alloc_box::<T>(T::ID, 1);
             data.as_ptr().write(value);
             data
         };
@@ -94,14 +91,14 @@ impl<T> AscBox<T> {
 }
 
 impl<T> AscBox<[T]> {
-    /// Creates a new boxed array value for a slice of T.
+    /// Creates a new boxed array buffer value for a slice of T.
     pub fn from_slice(items: &[T]) -> Self
     where
         T: Copy,
     {
         // SAFETY: `T` is copy, and the pointers are non-overalapping.
         let data = unsafe {
-            let data = alloc_box::<T>(TYPE_ID, items.len());
+            let data = alloc_box::<T>(class::ARRAY_BUFFER, items.len());
             ptr::copy_nonoverlapping(items.as_ptr(), data.as_ptr(), items.len());
             data
         };
@@ -109,15 +106,25 @@ impl<T> AscBox<[T]> {
         Self { data }
     }
 
-    /// Creates a new boxed array value with the specified length.
+    /// Creates a new boxed array buffer value with the specified length.
     ///
     /// # Panics
     ///
     /// Panics if the iterator is not of the specified length.
     pub fn with_len(len: usize, items: impl IntoIterator<Item = T>) -> Self {
+        Self::with_class_id(class::ARRAY_BUFFER, len, items)
+    }
+
+    /// Creates a new boxed array value of the specified class with the
+    /// specified length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not of the specified length.
+    pub fn with_class_id(id: u32, len: usize, items: impl IntoIterator<Item = T>) -> Self {
         let mut items = items.into_iter();
         let data = unsafe {
-            let data = alloc_box::<T>(TYPE_ID, len);
+            let data = alloc_box::<T>(id, len);
             let drop_array_and_panic = |count| {
                 drop_box::<T>(data, count);
                 panic!("iterator does not match specified length");
@@ -148,6 +155,17 @@ impl<T> AscBox<T>
 where
     T: AscBoxed + ?Sized,
 {
+    /// Creates a new box sharing the boxed value pointed to by `data`.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a boxed value, either allocated with [`alloc_box`]
+    /// or by the host.
+    unsafe fn retain(data: NonNull<T::Target>) -> Self {
+        AscHeader::for_data(data.as_ptr()).retain();
+        Self { data }
+    }
+
     /// Returns a reference to the AssemblyScript value.
     pub fn as_asc_ref(&self) -> &T::Ref {
         // SAFETY: [`AscBoxed`] trait implementation guarantees that `self` is
@@ -183,19 +201,11 @@ impl<T> Borrow<AscSlice<T>> for AscBox<[T]> {
 
 impl<T> Clone for AscBox<T>
 where
-    T: Clone,
-{
-    fn clone(&self) -> Self {
-        self.as_asc_ref().to_owned()
-    }
-}
-
-impl<T> Clone for AscBox<[T]>
-where
-    T: Clone,
+    T: AscBoxed + ?Sized,
 {
     fn clone(&self) -> Self {
-        self.as_asc_ref().to_owned()
+        // SAFETY: `data` is a valid boxed value pointer.
+        unsafe { Self::retain(self.data) }
     }
 }
 
@@ -220,6 +230,9 @@ where
             // reference we are creating here.
             let len = {
                 let header = AscHeader::for_data(self.data.as_ptr());
+                if !header.release() {
+                    return;
+                }
                 header.len::<T::Target>()
             };
             drop_box(self.data, len);
@@ -280,6 +293,179 @@ where
     }
 }
 
+/// A reference to an AssemblyScript value provided by the host.
+///
+/// Values provided by the host are never freed by the module, and live for the
+/// duration of the handler.
+///
+/// Note that this is only a checked conversion from a host pointer into a
+/// `'static` reference, and the checks are skipped in release builds. Code
+/// working with the resulting reference, including values nested within it,
+/// uses plain [`AscRef`]s that are not checked.
+pub struct HostRef<T>
+where
+    T: HostValue + 'static,
+{
+    inner: &'static T,
+}
+
+/// A value that can be referenced by pointers received from the host.
+///
+/// # Safety
+///
+/// Implementors must ensure that values are always preceded by an object
+/// header, and that `ALIGN` and `is_valid_len` are enough to guarantee that a
+/// pointer to an object is valid to dereference.
+pub unsafe trait HostValue {
+    /// The required alignment for pointers to the value.
+    const ALIGN: usize;
+
+    /// Returns `true` if an object of the specified length in bytes, as
+    /// specified by its header, is valid for the value.
+    fn is_valid_len(len: usize) -> bool;
+}
+
+// SAFETY: The object needs to be large enough for a `T`.
+unsafe impl<T> HostValue for AscRef<T> {
+    const ALIGN: usize = mem::align_of::<T>();
+
+    fn is_valid_len(len: usize) -> bool {
+        len >= mem::size_of::<T>()
+    }
+}
+
+// SAFETY: The object length is used as the length of the slice, so it needs to
+// be a multiple of the item size.
+unsafe impl<T> HostValue for AscSlice<T> {
+    const ALIGN: usize = mem::align_of::<T>();
+
+    fn is_valid_len(len: usize) -> bool {
+        len % mem::size_of::<T>() == 0
+    }
+}
+
+impl<T> HostRef<T>
+where
+    T: HostValue,
+{
+    /// Creates a reference from a pointer received from the host.
+    ///
+    /// In debug builds, the pointer is checked to be non-null, well-aligned
+    /// and to point to an object with a valid length within the module's
+    /// memory. Invalid pointers abort the handler with an error message.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been received from the host, either as a handler
+    /// parameter or as the result of a host function.
+    pub unsafe fn new(ptr: *const T) -> Self {
+        if cfg!(debug_assertions) {
+            check(ptr);
+        }
+
+        Self { inner: &*ptr }
+    }
+
+    /// Creates a reference from a nullable pointer received from the host,
+    /// returning `None` for null pointers.
+    ///
+    /// # Safety
+    ///
+    /// See [`HostRef::new`].
+    pub unsafe fn new_nullable(ptr: *const T) -> Option<Self> {
+        if ptr.is_null() {
+            return None;
+        }
+        Some(Self::new(ptr))
+    }
+
+    /// Returns the reference to the value.
+    pub fn into_ref(self) -> &'static T {
+        self.inner
+    }
+}
+
+impl<T> Clone for HostRef<T>
+where
+    T: HostValue,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for HostRef<T> where T: HostValue {}
+
+impl<T> Debug for HostRef<T>
+where
+    T: HostValue + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("HostRef").field(self.inner).finish()
+    }
+}
+
+impl<T> Deref for HostRef<T>
+where
+    T: HostValue,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner
+    }
+}
+
+/// Checks that a pointer received from the host points to a valid value.
+fn check<T>(ptr: *const T)
+where
+    T: HostValue,
+{
+    let fail = |reason: &dyn Display| -> ! {
+        panic!(
+            "invalid host pointer {ptr:p} to {}: {reason}",
+            any::type_name::<T>(),
+        )
+    };
+
+    let address = ptr as usize;
+    if address == 0 {
+        fail(&"null pointer");
+    }
+    if address % T::ALIGN != 0 {
+        fail(&format_args!("not aligned to {} bytes", T::ALIGN));
+    }
+    let header = match address.checked_sub(mem::size_of::<AscHeader>()) {
+        Some(header) if header % mem::align_of::<AscHeader>() == 0 => header,
+        _ => fail(&"invalid object header"),
+    };
+
+    // SAFETY: The header is non-null and well-aligned. The host allocates all
+    // objects with headers, so it is initialized.
+    let len = unsafe { (*(header as *const AscHeader)).rt_size } as usize;
+    if !T::is_valid_len(len) {
+        fail(&format_args!("invalid object length {len}"));
+    }
+    if address
+        .checked_add(len)
+        .map_or(true, |end| end > memory_end())
+    {
+        fail(&format_args!("object length {len} out of bounds"));
+    }
+}
+
+/// Returns the end of the module's memory.
+#[cfg(target_arch = "wasm32")]
+fn memory_end() -> usize {
+    const PAGE_SIZE: usize = 0x10000;
+    core::arch::wasm32::memory_size(0) * PAGE_SIZE
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_end() -> usize {
+    usize::MAX
+}
+
 /// Copy-on-write AssemblyScript boxed value.
 pub type AscCow<'a, T> = Cow<'a, <T as AscBoxed>::Ref>;
 
@@ -291,10 +477,7 @@ pub struct AscRef<T> {
 
 impl<T> AscRef<T> {
     /// Returns the AssemblyScript slice as a copy-on-write pointer.
-    pub fn borrowed(&self) -> AscCow<T>
-    where
-        T: Clone,
-    {
+    pub fn borrowed(&self) -> AscCow<'_, T> {
         Cow::Borrowed(self)
     }
 
@@ -327,14 +510,13 @@ impl<T> Deref for AscRef<T> {
     }
 }
 
-impl<T> ToOwned for AscRef<T>
-where
-    T: Clone,
-{
+impl<T> ToOwned for AscRef<T> {
     type Owned = AscBox<T>;
 
     fn to_owned(&self) -> Self::Owned {
-        AscBox::new(self.inner.clone())
+        // SAFETY: References to AssemblyScript values always point to boxed
+        // values.
+        unsafe { AscBox::retain(NonNull::from(&self.inner)) }
     }
 }
 
@@ -388,22 +570,26 @@ impl<T> Deref for AscSlice<T> {
     }
 }
 
-impl<T> ToOwned for AscSlice<T>
-where
-    T: Clone,
-{
+impl<T> ToOwned for AscSlice<T> {
     type Owned = AscBox<[T]>;
 
     fn to_owned(&self) -> Self::Owned {
-        self.as_slice().iter().cloned().collect()
+        // SAFETY: References to AssemblyScript arrays always point to boxed
+        // values.
+        unsafe { AscBox::retain(NonNull::from(&self.inner).cast()) }
     }
 }
 
 /// AssemblyScript object header.
+///
+/// The `gc_info` field holds the reference count of the object. Objects
+/// allocated by the host have a reference count of `0`, which marks them as
+/// unmanaged: they are never freed by the module and live for the duration of
+/// the handler.
 #[repr(C)]
 struct AscHeader {
     mm_info: usize,
-    gc_info: usize,
+    gc_info: Cell<usize>,
     gc_info2: usize,
     rt_id: u32,
     rt_size: u32,
@@ -433,6 +619,26 @@ impl AscHeader {
     fn len<T>(&self) -> usize {
         (self.rt_size as usize) / mem::size_of::<T>()
     }
+
+    /// Increments the reference count of a managed object.
+    fn retain(&self) {
+        let count = self.gc_info.get();
+        if count != 0 {
+            self.gc_info.set(count + 1);
+        }
+    }
+
+    /// Decrements the reference count of a managed object, returning `true`
+    /// if the last reference was released and the object should be freed.
+    fn release(&self) -> bool {
+        match self.gc_info.get() {
+            0 => false,
+            count => {
+                self.gc_info.set(count - 1);
+                count == 1
+            }
+        }
+    }
 }
 
 /// Allocates an AssemblyScript value and returns a pointer to **uninitialized**
@@ -485,7 +691,7 @@ fn alloc_box<T>(type_id: u32, len: usize) -> NonNull<T> {
     unsafe {
         let header = root.add(offset - header_size).cast::<AscHeader>();
         ptr::addr_of_mut!((*header).mm_info).write(layout.size());
-        ptr::addr_of_mut!((*header).gc_info).write(layout.align());
+        ptr::addr_of_mut!((*header).gc_info).write(Cell::new(1));
         ptr::addr_of_mut!((*header).gc_info2).write(offset);
         ptr::addr_of_mut!((*header).rt_id).write(type_id);
         ptr::addr_of_mut!((*header).rt_size).write(data_layout.size() as _);
@@ -506,9 +712,9 @@ unsafe fn drop_box<T>(data: NonNull<T>, len: usize) {
     // SAFETY: `data` is a valid object (ensured by the caller) and the header
     // reference does not outlive the data pointer, as it is dropped before we
     // actually de-allocate the pointer.
-    let (size, align, offset) = {
+    let (size, offset) = {
         let header = AscHeader::for_data(data.as_ptr());
-        (header.mm_info, header.gc_info, header.gc_info2)
+        (header.mm_info, header.gc_info2)
     };
 
     if mem::needs_drop::<T>() {
@@ -518,11 +724,186 @@ unsafe fn drop_box<T>(data: NonNull<T>, len: usize) {
         }
     }
 
-    // SAFETY: Layout is valid because we used it for allocation!
-    let layout = Layout::from_size_align_unchecked(size, align);
+    // SAFETY: Layout is valid because we used it for allocation! The
+    // alignment is the larger of the header and data alignments.
+    let layout = Layout::from_size_align_unchecked(size, ALIGN.max(mem::align_of::<T>()));
 
     // SAFETY: The root pointer is valid as that was the original allocation
     // with the layout that we just computed.
     let root = data.cast::<u8>().as_ptr().sub(offset);
     alloc::dealloc(root, layout)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// A value that counts how many times it was dropped.
+    struct Tracked(Rc<Cell<usize>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    impl AscClass for Tracked {
+        const ID: u32 = 42;
+    }
+
+    fn header<T>(value: &AscBox<T>) -> &AscHeader
+    where
+        T: AscBoxed + ?Sized,
+    {
+        unsafe { AscHeader::for_data(value.data.as_ptr()) }
+    }
+
+    #[test]
+    fn clone_then_drop_frees_once() {
+        let drops = Rc::new(Cell::new(0));
+        let value = AscBox::new(Tracked(drops.clone()));
+        assert_eq!(header(&value).gc_info.get(), 1);
+
+        let clone = value.clone();
+        assert_eq!(clone.as_ptr(), value.as_ptr());
+        assert_eq!(header(&value).gc_info.get(), 2);
+
+        drop(value);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(header(&clone).gc_info.get(), 1);
+
+        drop(clone);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn to_owned_shares_value() {
+        let drops = Rc::new(Cell::new(0));
+        let value = AscBox::new(Tracked(drops.clone()));
+        let owned = value.as_asc_ref().to_owned();
+        assert_eq!(owned.as_ptr(), value.as_ptr());
+        assert_eq!(header(&value).gc_info.get(), 2);
+
+        drop(value);
+        drop(owned);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn slice_clone_then_drop_frees_once() {
+        let drops = Rc::new(Cell::new(0));
+        let items = AscBox::<[_]>::with_len(3, (0..3).map(|_| Tracked(drops.clone())));
+        let clone = items.clone();
+        let owned = clone.as_asc_ref().to_owned();
+        assert_eq!(header(&items).gc_info.get(), 3);
+
+        drop(items);
+        drop(clone);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(owned.as_asc_ref().len(), 3);
+
+        drop(owned);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn host_objects_are_never_freed() {
+        let drops = Rc::new(Cell::new(0));
+        let value = AscBox::new(Tracked(drops.clone()));
+
+        // Objects allocated by the host have a reference count of 0.
+        header(&value).gc_info.set(0);
+        let owned = value.as_asc_ref().to_owned();
+        let clone = owned.clone();
+        assert_eq!(header(&value).gc_info.get(), 0);
+
+        drop(owned);
+        drop(clone);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(header(&value).gc_info.get(), 0);
+
+        // Restore the reference count so that the test value gets freed.
+        header(&value).gc_info.set(1);
+        drop(value);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn headers_contain_class_ids() {
+        let drops = Rc::new(Cell::new(0));
+        let value = AscBox::new(Tracked(drops));
+        assert_eq!(header(&value).rt_id, Tracked::ID);
+        assert_eq!(header(&value).rt_size as usize, mem::size_of::<Tracked>());
+
+        let bytes = AscBox::<[u8]>::from_slice(&[1, 2, 3]);
+        assert_eq!(header(&bytes).rt_id, class::ARRAY_BUFFER);
+        assert_eq!(header(&bytes).rt_size, 3);
+
+        let items = AscBox::<[u64]>::with_class_id(7, 2, [1, 2]);
+        assert_eq!(header(&items).rt_id, 7);
+        assert_eq!(header(&items).rt_size, 16);
+    }
+
+    #[test]
+    fn host_ref_accepts_valid_pointers() {
+        let value = AscBox::new(Tracked(Rc::new(Cell::new(0))));
+        let host = unsafe { HostRef::new(value.as_ptr()) };
+        assert_eq!(host.into_ref().as_ptr(), value.as_ptr());
+
+        let items = AscBox::<[u64]>::from_slice(&[1, 2]);
+        let host = unsafe { HostRef::new(items.as_ptr()) };
+        assert_eq!(host.as_slice(), [1, 2]);
+
+        let empty = AscBox::<[u64]>::from_slice(&[]);
+        let host = unsafe { HostRef::new(empty.as_ptr()) };
+        assert!(host.as_slice().is_empty());
+    }
+
+    #[test]
+    fn host_ref_nullable() {
+        let host = unsafe { HostRef::new_nullable(ptr::null::<AscRef<u64>>()) };
+        assert!(host.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "null pointer")]
+    fn check_null() {
+        check(ptr::null::<AscRef<u64>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "not aligned to 8 bytes")]
+    fn check_misaligned() {
+        let items = AscBox::<[u64]>::from_slice(&[1, 2]);
+        check(
+            items
+                .as_ptr()
+                .cast::<u8>()
+                .wrapping_add(4)
+                .cast::<AscSlice<u64>>(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid object header")]
+    fn check_missing_header() {
+        check(mem::align_of::<u64>() as *const AscRef<u64>);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid object length 4")]
+    fn check_short_value() {
+        let bytes = AscBox::<[u8]>::from_slice(&[0; 4]);
+        check(bytes.as_ptr().cast::<AscRef<u32>>());
+        check(bytes.as_ptr().cast::<AscRef<u64>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid object length 6")]
+    fn check_partial_slice_item() {
+        let bytes = AscBox::<[u8]>::from_slice(&[0; 6]);
+        check(bytes.as_ptr().cast::<AscSlice<u16>>());
+        check(bytes.as_ptr().cast::<AscSlice<u32>>());
+    }
+}
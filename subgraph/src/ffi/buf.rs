@@ -1,6 +1,9 @@
 //! AssemblyScript buffer and typed array definitions.
 
-use super::boxed::AscBox;
+use super::{
+    boxed::AscBox,
+    class::{AscClass, TypeIndex},
+};
 use std::{mem, slice};
 
 /// An AssemblyScript ArrayBuffer.
@@ -32,9 +35,6 @@ impl AscArrayBuffer {
 /// A typed array view into an array buffer.
 #[repr(C)]
 pub struct AscTypedArray<T> {
-    // FIXME(nlordell): In theory, this is a reference to an array buffer.
-    // However, we currently don't share array buffer data, and having the view
-    // own the buffer simplifies things in a lot of places.
     buffer: AscArrayBuffer,
     data_start: *const T,
     byte_length: usize,
@@ -45,7 +45,10 @@ where
     T: AscTypedArrayItem,
 {
     /// Creates a new typed array
-    pub fn new(buffer: AscArrayBuffer) -> AscBox<Self> {
+    pub fn new(buffer: AscArrayBuffer) -> AscBox<Self>
+    where
+        Self: AscClass,
+    {
         let len = buffer.as_bytes().len();
         let trailing = len % mem::size_of::<T>();
 
@@ -77,22 +80,21 @@ impl AscTypedArray<u8> {
     }
 }
 
-impl<T> Clone for AscTypedArray<T>
-where
-    T: AscTypedArrayItem,
-{
+impl<T> Clone for AscTypedArray<T> {
     fn clone(&self) -> Self {
-        let buffer = self.buffer.clone();
-        let data_start = buffer.as_ptr().cast();
-
+        // NOTE: The buffer is shared, so the data pointer remains valid.
         Self {
-            buffer,
-            data_start,
+            buffer: self.buffer.clone(),
+            data_start: self.data_start,
             byte_length: self.byte_length,
         }
     }
 }
 
+impl AscClass for AscTypedArray<u8> {
+    const ID: u32 = TypeIndex::Uint8Array.class_id();
+}
+
 /// A marker trait indicating that a type can be used by a typed array view.
 ///
 /// # Safety
@@ -0,0 +1,188 @@
+//! AssemblyScript runtime class IDs.
+//!
+//! The AssemblyScript runtime stores the class ID of every managed object in
+//! its header. The Subgraph host does not know the class IDs of the module it
+//! is running, and instead asks for them through the `id_of_type` export with
+//! its own type indices.
+
+/// The class ID of the AssemblyScript `ArrayBuffer` class.
+pub const ARRAY_BUFFER: u32 = 1;
+
+/// The class ID of the AssemblyScript `String` class.
+pub const STRING: u32 = 2;
+
+/// The class ID of the AssemblyScript `ArrayBufferView` class.
+pub const ARRAY_BUFFER_VIEW: u32 = 3;
+
+/// An AssemblyScript class with a runtime class ID.
+pub trait AscClass {
+    /// The runtime class ID written to the object header on allocation.
+    const ID: u32;
+}
+
+/// Type indices used by the Subgraph host to refer to AssemblyScript classes.
+///
+/// These match the values of the host's `IndexForAscTypeId` enumeration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum TypeIndex {
+    String = 0,
+    ArrayBuffer = 1,
+    Uint8Array = 6,
+    BigDecimal = 12,
+    ArrayEthereumValue = 15,
+    ArrayStoreValue = 16,
+    ArrayJsonValue = 17,
+    ArrayString = 18,
+    ArrayTypedMapEntryStringJsonValue = 20,
+    ArrayTypedMapEntryStringStoreValue = 21,
+    SmartContractCall = 22,
+    EthereumValue = 30,
+    StoreValue = 31,
+    JsonValue = 32,
+    TypedMapEntryStringStoreValue = 34,
+    TypedMapEntryStringJsonValue = 35,
+    TypedMapStringStoreValue = 36,
+    TypedMapStringJsonValue = 37,
+}
+
+impl TypeIndex {
+    /// Returns the class ID for the type index.
+    pub const fn class_id(self) -> u32 {
+        class_id(self as _)
+    }
+}
+
+/// Returns the class ID for a host type index.
+///
+/// The `String` and `ArrayBuffer` types map to their built-in AssemblyScript
+/// classes, and all other types are assigned consecutive class IDs following
+/// the built-in ones.
+///
+/// # Panics
+///
+/// Panics if the type index is out of range.
+pub const fn class_id(index: u32) -> u32 {
+    // NOTE: The first non built-in type index is `2`, which maps to the first
+    // class ID following `ArrayBufferView`.
+    const OFFSET: u32 = ARRAY_BUFFER_VIEW + 1 - 2;
+
+    if index == TypeIndex::String as _ {
+        STRING
+    } else if index == TypeIndex::ArrayBuffer as _ {
+        ARRAY_BUFFER
+    } else {
+        match index.checked_add(OFFSET) {
+            Some(id) => id,
+            None => panic!("type index out of range"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The values of graph-node's `IndexForAscTypeId` enumeration, copied from
+    /// `graph/src/runtime/mod.rs`.
+    ///
+    /// <https://github.com/graphprotocol/graph-node/blob/master/graph/src/runtime/mod.rs>
+    const GRAPH_NODE_INDICES: &[(&str, u32)] = &[
+        ("String", 0),
+        ("ArrayBuffer", 1),
+        ("Int8Array", 2),
+        ("Int16Array", 3),
+        ("Int32Array", 4),
+        ("Int64Array", 5),
+        ("Uint8Array", 6),
+        ("Uint16Array", 7),
+        ("Uint32Array", 8),
+        ("Uint64Array", 9),
+        ("Float32Array", 10),
+        ("Float64Array", 11),
+        ("BigDecimal", 12),
+        ("ArrayBool", 13),
+        ("ArrayUint8Array", 14),
+        ("ArrayEthereumValue", 15),
+        ("ArrayStoreValue", 16),
+        ("ArrayJsonValue", 17),
+        ("ArrayString", 18),
+        ("ArrayEventParam", 19),
+        ("ArrayTypedMapEntryStringJsonValue", 20),
+        ("ArrayTypedMapEntryStringStoreValue", 21),
+        ("SmartContractCall", 22),
+        ("EventParam", 23),
+        ("EthereumTransaction", 24),
+        ("EthereumBlock", 25),
+        ("EthereumCall", 26),
+        ("WrappedTypedMapStringJsonValue", 27),
+        ("WrappedBool", 28),
+        ("WrappedJsonValue", 29),
+        ("EthereumValue", 30),
+        ("StoreValue", 31),
+        ("JsonValue", 32),
+        ("EthereumEvent", 33),
+        ("TypedMapEntryStringStoreValue", 34),
+        ("TypedMapEntryStringJsonValue", 35),
+        ("TypedMapStringStoreValue", 36),
+        ("TypedMapStringJsonValue", 37),
+    ];
+
+    const ALL: &[TypeIndex] = &[
+        TypeIndex::String,
+        TypeIndex::ArrayBuffer,
+        TypeIndex::Uint8Array,
+        TypeIndex::BigDecimal,
+        TypeIndex::ArrayEthereumValue,
+        TypeIndex::ArrayStoreValue,
+        TypeIndex::ArrayJsonValue,
+        TypeIndex::ArrayString,
+        TypeIndex::ArrayTypedMapEntryStringJsonValue,
+        TypeIndex::ArrayTypedMapEntryStringStoreValue,
+        TypeIndex::SmartContractCall,
+        TypeIndex::EthereumValue,
+        TypeIndex::StoreValue,
+        TypeIndex::JsonValue,
+        TypeIndex::TypedMapEntryStringStoreValue,
+        TypeIndex::TypedMapEntryStringJsonValue,
+        TypeIndex::TypedMapStringStoreValue,
+        TypeIndex::TypedMapStringJsonValue,
+    ];
+
+    #[test]
+    fn type_indices_match_graph_node() {
+        for &index in ALL {
+            let name = format!("{index:?}");
+            let id = GRAPH_NODE_INDICES
+                .iter()
+                .find(|(graph_node_name, _)| *graph_node_name == name)
+                .map(|(_, id)| *id);
+            assert_eq!(Some(index as u32), id, "{name}");
+        }
+    }
+
+    #[test]
+    fn class_ids() {
+        assert_eq!(TypeIndex::String.class_id(), STRING);
+        assert_eq!(TypeIndex::ArrayBuffer.class_id(), ARRAY_BUFFER);
+
+        // Class IDs of other types follow the built-in classes, without
+        // colliding with each other.
+        let mut ids = Vec::new();
+        for index in 0..64 {
+            let id = class_id(index);
+            assert!(!ids.contains(&id), "duplicate class ID {id}");
+            ids.push(id);
+        }
+        assert!(!ids.contains(&ARRAY_BUFFER_VIEW));
+        assert_eq!(class_id(2), ARRAY_BUFFER_VIEW + 1);
+        assert_eq!(TypeIndex::Uint8Array.class_id(), 8);
+        assert_eq!(TypeIndex::TypedMapStringJsonValue.class_id(), 39);
+    }
+
+    #[test]
+    #[should_panic(expected = "type index out of range")]
+    fn class_id_out_of_range() {
+        class_id(u32::MAX);
+    }
+}
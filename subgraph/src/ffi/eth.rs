@@ -4,6 +4,7 @@
 
 use super::{
     boxed::{AscBox, AscNullableBox, AscRef},
+    class::{AscClass, TypeIndex},
     num::AscBigInt,
     str::{AscNullableString, AscStr, AscString},
     types::{AscAddress, AscBytes},
@@ -390,3 +391,7 @@ impl AscEthereumSmartContractCall {
         })
     }
 }
+
+impl AscClass for AscEthereumSmartContractCall {
+    const ID: u32 = TypeIndex::SmartContractCall.class_id();
+}
@@ -0,0 +1,684 @@
+//! NEAR-specific type definitions.
+//!
+//! The layouts match the AssemblyScript classes that the host generates for
+//! NEAR block and receipt handlers.
+
+use super::{
+    boxed::{AscBox, AscNullableBox, AscRef},
+    num::AscBigInt,
+    str::{AscStr, AscString},
+    types::AscBytes,
+    value::{asc_tagged_union, asc_tagged_union_field, AscArray},
+};
+use std::mem::ManuallyDrop;
+
+/// A NEAR cryptographic hash.
+pub type AscCryptoHash = AscBytes;
+
+/// A NEAR block.
+#[repr(C)]
+pub struct AscBlock {
+    author: AscString,
+    header: AscBox<AscBlockHeader>,
+    chunks: AscBox<AscArray<AscBox<AscChunkHeader>>>,
+}
+
+impl AscBlock {
+    pub(crate) fn author(&self) -> &AscStr {
+        self.author.as_asc_str()
+    }
+
+    pub(crate) fn header(&self) -> &AscRef<AscBlockHeader> {
+        self.header.as_asc_ref()
+    }
+
+    pub(crate) fn chunks(&self) -> &AscRef<AscArray<AscBox<AscChunkHeader>>> {
+        self.chunks.as_asc_ref()
+    }
+}
+
+/// A NEAR block header.
+#[repr(C)]
+pub struct AscBlockHeader {
+    height: u64,
+    prev_height: u64,
+    epoch_id: AscBox<AscCryptoHash>,
+    next_epoch_id: AscBox<AscCryptoHash>,
+    hash: AscBox<AscCryptoHash>,
+    prev_hash: AscBox<AscCryptoHash>,
+    prev_state_root: AscBox<AscCryptoHash>,
+    chunk_receipts_root: AscBox<AscCryptoHash>,
+    chunk_headers_root: AscBox<AscCryptoHash>,
+    chunk_tx_root: AscBox<AscCryptoHash>,
+    outcome_root: AscBox<AscCryptoHash>,
+    challenges_root: AscBox<AscCryptoHash>,
+    random_value: AscBox<AscCryptoHash>,
+    validator_proposals: AscBox<AscArray<AscBox<AscValidatorStake>>>,
+    chunk_mask: AscBox<AscArray<bool>>,
+    gas_price: AscBox<AscBigInt>,
+    block_ordinal: u64,
+    total_supply: AscBox<AscBigInt>,
+    challenges_result: AscBox<AscArray<AscBox<AscSlashedValidator>>>,
+    last_final_block: AscBox<AscCryptoHash>,
+    last_ds_final_block: AscBox<AscCryptoHash>,
+    next_bp_hash: AscBox<AscCryptoHash>,
+    block_merkle_root: AscBox<AscCryptoHash>,
+    epoch_sync_data_hash: AscBox<AscBytes>,
+    approvals: AscBox<AscArray<AscBox<AscSignature>>>,
+    signature: AscBox<AscSignature>,
+    latest_protocol_version: u32,
+}
+
+impl AscBlockHeader {
+    pub(crate) fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub(crate) fn prev_height(&self) -> u64 {
+        self.prev_height
+    }
+
+    pub(crate) fn epoch_id(&self) -> &AscRef<AscCryptoHash> {
+        self.epoch_id.as_asc_ref()
+    }
+
+    pub(crate) fn next_epoch_id(&self) -> &AscRef<AscCryptoHash> {
+        self.next_epoch_id.as_asc_ref()
+    }
+
+    pub(crate) fn hash(&self) -> &AscRef<AscCryptoHash> {
+        self.hash.as_asc_ref()
+    }
+
+    pub(crate) fn prev_hash(&self) -> &AscRef<AscCryptoHash> {
+        self.prev_hash.as_asc_ref()
+    }
+
+    pub(crate) fn prev_state_root(&self) -> &AscRef<AscCryptoHash> {
+        self.prev_state_root.as_asc_ref()
+    }
+
+    pub(crate) fn chunk_receipts_root(&self) -> &AscRef<AscCryptoHash> {
+        self.chunk_receipts_root.as_asc_ref()
+    }
+
+    pub(crate) fn chunk_headers_root(&self) -> &AscRef<AscCryptoHash> {
+        self.chunk_headers_root.as_asc_ref()
+    }
+
+    pub(crate) fn chunk_tx_root(&self) -> &AscRef<AscCryptoHash> {
+        self.chunk_tx_root.as_asc_ref()
+    }
+
+    pub(crate) fn outcome_root(&self) -> &AscRef<AscCryptoHash> {
+        self.outcome_root.as_asc_ref()
+    }
+
+    pub(crate) fn challenges_root(&self) -> &AscRef<AscCryptoHash> {
+        self.challenges_root.as_asc_ref()
+    }
+
+    pub(crate) fn random_value(&self) -> &AscRef<AscCryptoHash> {
+        self.random_value.as_asc_ref()
+    }
+
+    pub(crate) fn validator_proposals(&self) -> &AscRef<AscArray<AscBox<AscValidatorStake>>> {
+        self.validator_proposals.as_asc_ref()
+    }
+
+    pub(crate) fn chunk_mask(&self) -> &AscRef<AscArray<bool>> {
+        self.chunk_mask.as_asc_ref()
+    }
+
+    pub(crate) fn gas_price(&self) -> &AscRef<AscBigInt> {
+        self.gas_price.as_asc_ref()
+    }
+
+    pub(crate) fn block_ordinal(&self) -> u64 {
+        self.block_ordinal
+    }
+
+    pub(crate) fn total_supply(&self) -> &AscRef<AscBigInt> {
+        self.total_supply.as_asc_ref()
+    }
+
+    pub(crate) fn challenges_result(&self) -> &AscRef<AscArray<AscBox<AscSlashedValidator>>> {
+        self.challenges_result.as_asc_ref()
+    }
+
+    pub(crate) fn last_final_block(&self) -> &AscRef<AscCryptoHash> {
+        self.last_final_block.as_asc_ref()
+    }
+
+    pub(crate) fn last_ds_final_block(&self) -> &AscRef<AscCryptoHash> {
+        self.last_ds_final_block.as_asc_ref()
+    }
+
+    pub(crate) fn next_bp_hash(&self) -> &AscRef<AscCryptoHash> {
+        self.next_bp_hash.as_asc_ref()
+    }
+
+    pub(crate) fn block_merkle_root(&self) -> &AscRef<AscCryptoHash> {
+        self.block_merkle_root.as_asc_ref()
+    }
+
+    pub(crate) fn epoch_sync_data_hash(&self) -> &AscRef<AscBytes> {
+        self.epoch_sync_data_hash.as_asc_ref()
+    }
+
+    pub(crate) fn approvals(&self) -> &AscRef<AscArray<AscBox<AscSignature>>> {
+        self.approvals.as_asc_ref()
+    }
+
+    pub(crate) fn signature(&self) -> &AscRef<AscSignature> {
+        self.signature.as_asc_ref()
+    }
+
+    pub(crate) fn latest_protocol_version(&self) -> u32 {
+        self.latest_protocol_version
+    }
+}
+
+/// A NEAR chunk header.
+#[repr(C)]
+pub struct AscChunkHeader {
+    encoded_length: u64,
+    gas_used: u64,
+    gas_limit: u64,
+    shard_id: u64,
+    height_created: u64,
+    height_included: u64,
+    chunk_hash: AscBox<AscCryptoHash>,
+    signature: AscBox<AscSignature>,
+    prev_block_hash: AscBox<AscCryptoHash>,
+    prev_state_root: AscBox<AscCryptoHash>,
+    encoded_merkle_root: AscBox<AscCryptoHash>,
+    balance_burnt: AscBox<AscBigInt>,
+    outgoing_receipts_root: AscBox<AscCryptoHash>,
+    tx_root: AscBox<AscCryptoHash>,
+    validator_proposals: AscBox<AscArray<AscBox<AscValidatorStake>>>,
+}
+
+impl AscChunkHeader {
+    pub(crate) fn encoded_length(&self) -> u64 {
+        self.encoded_length
+    }
+
+    pub(crate) fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    pub(crate) fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    pub(crate) fn shard_id(&self) -> u64 {
+        self.shard_id
+    }
+
+    pub(crate) fn height_created(&self) -> u64 {
+        self.height_created
+    }
+
+    pub(crate) fn height_included(&self) -> u64 {
+        self.height_included
+    }
+
+    pub(crate) fn chunk_hash(&self) -> &AscRef<AscCryptoHash> {
+        self.chunk_hash.as_asc_ref()
+    }
+
+    pub(crate) fn signature(&self) -> &AscRef<AscSignature> {
+        self.signature.as_asc_ref()
+    }
+
+    pub(crate) fn prev_block_hash(&self) -> &AscRef<AscCryptoHash> {
+        self.prev_block_hash.as_asc_ref()
+    }
+
+    pub(crate) fn prev_state_root(&self) -> &AscRef<AscCryptoHash> {
+        self.prev_state_root.as_asc_ref()
+    }
+
+    pub(crate) fn encoded_merkle_root(&self) -> &AscRef<AscCryptoHash> {
+        self.encoded_merkle_root.as_asc_ref()
+    }
+
+    pub(crate) fn balance_burnt(&self) -> &AscRef<AscBigInt> {
+        self.balance_burnt.as_asc_ref()
+    }
+
+    pub(crate) fn outgoing_receipts_root(&self) -> &AscRef<AscCryptoHash> {
+        self.outgoing_receipts_root.as_asc_ref()
+    }
+
+    pub(crate) fn tx_root(&self) -> &AscRef<AscCryptoHash> {
+        self.tx_root.as_asc_ref()
+    }
+
+    pub(crate) fn validator_proposals(&self) -> &AscRef<AscArray<AscBox<AscValidatorStake>>> {
+        self.validator_proposals.as_asc_ref()
+    }
+}
+
+/// A NEAR public key.
+#[repr(C)]
+pub struct AscPublicKey {
+    kind: i32,
+    bytes: AscBox<AscBytes>,
+}
+
+impl AscPublicKey {
+    pub(crate) fn kind(&self) -> i32 {
+        self.kind
+    }
+
+    pub(crate) fn bytes(&self) -> &AscRef<AscBytes> {
+        self.bytes.as_asc_ref()
+    }
+}
+
+/// A NEAR signature.
+#[repr(C)]
+pub struct AscSignature {
+    kind: i32,
+    bytes: AscBox<AscBytes>,
+}
+
+impl AscSignature {
+    pub(crate) fn kind(&self) -> i32 {
+        self.kind
+    }
+
+    pub(crate) fn bytes(&self) -> &AscRef<AscBytes> {
+        self.bytes.as_asc_ref()
+    }
+}
+
+/// A NEAR validator stake.
+#[repr(C)]
+pub struct AscValidatorStake {
+    account_id: AscString,
+    public_key: AscBox<AscPublicKey>,
+    stake: AscBox<AscBigInt>,
+}
+
+impl AscValidatorStake {
+    pub(crate) fn account_id(&self) -> &AscStr {
+        self.account_id.as_asc_str()
+    }
+
+    pub(crate) fn public_key(&self) -> &AscRef<AscPublicKey> {
+        self.public_key.as_asc_ref()
+    }
+
+    pub(crate) fn stake(&self) -> &AscRef<AscBigInt> {
+        self.stake.as_asc_ref()
+    }
+}
+
+/// A NEAR slashed validator.
+#[repr(C)]
+pub struct AscSlashedValidator {
+    account_id: AscString,
+    is_double_sign: bool,
+}
+
+impl AscSlashedValidator {
+    pub(crate) fn account_id(&self) -> &AscStr {
+        self.account_id.as_asc_str()
+    }
+
+    pub(crate) fn is_double_sign(&self) -> bool {
+        self.is_double_sign
+    }
+}
+
+/// A NEAR receipt along with its execution outcome.
+#[repr(C)]
+pub struct AscReceiptWithOutcome {
+    outcome: AscBox<AscExecutionOutcome>,
+    receipt: AscBox<AscActionReceipt>,
+    block: AscBox<AscBlock>,
+}
+
+impl AscReceiptWithOutcome {
+    pub(crate) fn outcome(&self) -> &AscRef<AscExecutionOutcome> {
+        self.outcome.as_asc_ref()
+    }
+
+    pub(crate) fn receipt(&self) -> &AscRef<AscActionReceipt> {
+        self.receipt.as_asc_ref()
+    }
+
+    pub(crate) fn block(&self) -> &AscRef<AscBlock> {
+        self.block.as_asc_ref()
+    }
+}
+
+/// A NEAR action receipt.
+#[repr(C)]
+pub struct AscActionReceipt {
+    predecessor_id: AscString,
+    receiver_id: AscString,
+    id: AscBox<AscCryptoHash>,
+    signer_id: AscString,
+    signer_public_key: AscBox<AscPublicKey>,
+    gas_price: AscBox<AscBigInt>,
+    output_data_receivers: AscBox<AscArray<AscBox<AscDataReceiver>>>,
+    input_data_ids: AscBox<AscArray<AscBox<AscCryptoHash>>>,
+    actions: AscBox<AscArray<AscBox<AscAction>>>,
+}
+
+impl AscActionReceipt {
+    pub(crate) fn predecessor_id(&self) -> &AscStr {
+        self.predecessor_id.as_asc_str()
+    }
+
+    pub(crate) fn receiver_id(&self) -> &AscStr {
+        self.receiver_id.as_asc_str()
+    }
+
+    pub(crate) fn id(&self) -> &AscRef<AscCryptoHash> {
+        self.id.as_asc_ref()
+    }
+
+    pub(crate) fn signer_id(&self) -> &AscStr {
+        self.signer_id.as_asc_str()
+    }
+
+    pub(crate) fn signer_public_key(&self) -> &AscRef<AscPublicKey> {
+        self.signer_public_key.as_asc_ref()
+    }
+
+    pub(crate) fn gas_price(&self) -> &AscRef<AscBigInt> {
+        self.gas_price.as_asc_ref()
+    }
+
+    pub(crate) fn output_data_receivers(&self) -> &AscRef<AscArray<AscBox<AscDataReceiver>>> {
+        self.output_data_receivers.as_asc_ref()
+    }
+
+    pub(crate) fn input_data_ids(&self) -> &AscRef<AscArray<AscBox<AscCryptoHash>>> {
+        self.input_data_ids.as_asc_ref()
+    }
+
+    pub(crate) fn actions(&self) -> &AscRef<AscArray<AscBox<AscAction>>> {
+        self.actions.as_asc_ref()
+    }
+}
+
+/// A NEAR data receiver.
+#[repr(C)]
+pub struct AscDataReceiver {
+    data_id: AscBox<AscCryptoHash>,
+    receiver_id: AscString,
+}
+
+impl AscDataReceiver {
+    pub(crate) fn data_id(&self) -> &AscRef<AscCryptoHash> {
+        self.data_id.as_asc_ref()
+    }
+
+    pub(crate) fn receiver_id(&self) -> &AscStr {
+        self.receiver_id.as_asc_str()
+    }
+}
+
+/// A NEAR receipt execution outcome.
+#[repr(C)]
+pub struct AscExecutionOutcome {
+    gas_burnt: u64,
+    proof: AscBox<AscArray<AscBox<AscMerklePathItem>>>,
+    block_hash: AscBox<AscCryptoHash>,
+    id: AscBox<AscCryptoHash>,
+    logs: AscBox<AscArray<AscString>>,
+    receipt_ids: AscBox<AscArray<AscBox<AscCryptoHash>>>,
+    tokens_burnt: AscBox<AscBigInt>,
+    executor_id: AscString,
+    status: AscBox<AscSuccessStatus>,
+}
+
+impl AscExecutionOutcome {
+    pub(crate) fn gas_burnt(&self) -> u64 {
+        self.gas_burnt
+    }
+
+    pub(crate) fn proof(&self) -> &AscRef<AscArray<AscBox<AscMerklePathItem>>> {
+        self.proof.as_asc_ref()
+    }
+
+    pub(crate) fn block_hash(&self) -> &AscRef<AscCryptoHash> {
+        self.block_hash.as_asc_ref()
+    }
+
+    pub(crate) fn id(&self) -> &AscRef<AscCryptoHash> {
+        self.id.as_asc_ref()
+    }
+
+    pub(crate) fn logs(&self) -> &AscRef<AscArray<AscString>> {
+        self.logs.as_asc_ref()
+    }
+
+    pub(crate) fn receipt_ids(&self) -> &AscRef<AscArray<AscBox<AscCryptoHash>>> {
+        self.receipt_ids.as_asc_ref()
+    }
+
+    pub(crate) fn tokens_burnt(&self) -> &AscRef<AscBigInt> {
+        self.tokens_burnt.as_asc_ref()
+    }
+
+    pub(crate) fn executor_id(&self) -> &AscStr {
+        self.executor_id.as_asc_str()
+    }
+
+    pub(crate) fn status(&self) -> &AscRef<AscSuccessStatus> {
+        self.status.as_asc_ref()
+    }
+}
+
+/// A NEAR Merkle proof path item.
+#[repr(C)]
+pub struct AscMerklePathItem {
+    hash: AscBox<AscCryptoHash>,
+    direction: u32,
+}
+
+impl AscMerklePathItem {
+    pub(crate) fn hash(&self) -> &AscRef<AscCryptoHash> {
+        self.hash.as_asc_ref()
+    }
+
+    pub(crate) fn direction(&self) -> u32 {
+        self.direction
+    }
+}
+
+asc_tagged_union! {
+    /// A NEAR successful execution status.
+    AscSuccessStatus,
+    AscSuccessStatusKind,
+    AscSuccessStatusPayload,
+    AscSuccessStatusData {
+        Value, value (boxed AscBytes) = 0,
+        ReceiptId, receipt_id (boxed AscCryptoHash) = 1,
+    }
+}
+
+asc_tagged_union! {
+    /// A NEAR receipt action.
+    AscAction,
+    AscActionKind,
+    AscActionPayload,
+    AscActionData {
+        // NOTE: The payloads of actions and permissions without any data point
+        // to empty objects, so we don't bother reading them.
+        CreateAccount, create_account (null) = 0,
+        DeployContract, deploy_contract (boxed AscDeployContractAction) = 1,
+        FunctionCall, function_call (boxed AscFunctionCallAction) = 2,
+        Transfer, transfer (boxed AscTransferAction) = 3,
+        Stake, stake (boxed AscStakeAction) = 4,
+        AddKey, add_key (boxed AscAddKeyAction) = 5,
+        DeleteKey, delete_key (boxed AscDeleteKeyAction) = 6,
+        DeleteAccount, delete_account (boxed AscDeleteAccountAction) = 7,
+    }
+}
+
+/// A NEAR deploy contract action.
+#[repr(C)]
+pub struct AscDeployContractAction {
+    code: AscBox<AscBytes>,
+}
+
+impl AscDeployContractAction {
+    pub(crate) fn code(&self) -> &AscRef<AscBytes> {
+        self.code.as_asc_ref()
+    }
+}
+
+/// A NEAR function call action.
+#[repr(C)]
+pub struct AscFunctionCallAction {
+    method_name: AscString,
+    args: AscBox<AscBytes>,
+    gas: u64,
+    deposit: AscBox<AscBigInt>,
+}
+
+impl AscFunctionCallAction {
+    pub(crate) fn method_name(&self) -> &AscStr {
+        self.method_name.as_asc_str()
+    }
+
+    pub(crate) fn args(&self) -> &AscRef<AscBytes> {
+        self.args.as_asc_ref()
+    }
+
+    pub(crate) fn gas(&self) -> u64 {
+        self.gas
+    }
+
+    pub(crate) fn deposit(&self) -> &AscRef<AscBigInt> {
+        self.deposit.as_asc_ref()
+    }
+}
+
+/// A NEAR transfer action.
+#[repr(C)]
+pub struct AscTransferAction {
+    deposit: AscBox<AscBigInt>,
+}
+
+impl AscTransferAction {
+    pub(crate) fn deposit(&self) -> &AscRef<AscBigInt> {
+        self.deposit.as_asc_ref()
+    }
+}
+
+/// A NEAR stake action.
+#[repr(C)]
+pub struct AscStakeAction {
+    stake: AscBox<AscBigInt>,
+    public_key: AscBox<AscPublicKey>,
+}
+
+impl AscStakeAction {
+    pub(crate) fn stake(&self) -> &AscRef<AscBigInt> {
+        self.stake.as_asc_ref()
+    }
+
+    pub(crate) fn public_key(&self) -> &AscRef<AscPublicKey> {
+        self.public_key.as_asc_ref()
+    }
+}
+
+/// A NEAR add key action.
+#[repr(C)]
+pub struct AscAddKeyAction {
+    public_key: AscBox<AscPublicKey>,
+    access_key: AscBox<AscAccessKey>,
+}
+
+impl AscAddKeyAction {
+    pub(crate) fn public_key(&self) -> &AscRef<AscPublicKey> {
+        self.public_key.as_asc_ref()
+    }
+
+    pub(crate) fn access_key(&self) -> &AscRef<AscAccessKey> {
+        self.access_key.as_asc_ref()
+    }
+}
+
+/// A NEAR delete key action.
+#[repr(C)]
+pub struct AscDeleteKeyAction {
+    public_key: AscBox<AscPublicKey>,
+}
+
+impl AscDeleteKeyAction {
+    pub(crate) fn public_key(&self) -> &AscRef<AscPublicKey> {
+        self.public_key.as_asc_ref()
+    }
+}
+
+/// A NEAR delete account action.
+#[repr(C)]
+pub struct AscDeleteAccountAction {
+    beneficiary_id: AscString,
+}
+
+impl AscDeleteAccountAction {
+    pub(crate) fn beneficiary_id(&self) -> &AscStr {
+        self.beneficiary_id.as_asc_str()
+    }
+}
+
+/// A NEAR access key.
+#[repr(C)]
+pub struct AscAccessKey {
+    nonce: u64,
+    permission: AscBox<AscAccessKeyPermission>,
+}
+
+impl AscAccessKey {
+    pub(crate) fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub(crate) fn permission(&self) -> &AscRef<AscAccessKeyPermission> {
+        self.permission.as_asc_ref()
+    }
+}
+
+asc_tagged_union! {
+    /// A NEAR access key permission.
+    AscAccessKeyPermission,
+    AscAccessKeyPermissionKind,
+    AscAccessKeyPermissionPayload,
+    AscAccessKeyPermissionData {
+        FunctionCall, function_call (boxed AscFunctionCallPermission) = 0,
+        FullAccess, full_access (null) = 1,
+    }
+}
+
+/// A NEAR function call access key permission.
+#[repr(C)]
+pub struct AscFunctionCallPermission {
+    allowance: AscNullableBox<AscBigInt>,
+    receiver_id: AscString,
+    method_names: AscBox<AscArray<AscString>>,
+}
+
+impl AscFunctionCallPermission {
+    pub(crate) fn allowance(&self) -> Option<&AscRef<AscBigInt>> {
+        self.allowance.as_asc_ref()
+    }
+
+    pub(crate) fn receiver_id(&self) -> &AscStr {
+        self.receiver_id.as_asc_str()
+    }
+
+    pub(crate) fn method_names(&self) -> &AscRef<AscArray<AscString>> {
+        self.method_names.as_asc_ref()
+    }
+}
@@ -3,6 +3,7 @@
 use super::{
     boxed::{AscBox, AscRef},
     buf::AscTypedArray,
+    class::{AscClass, TypeIndex},
 };
 
 /// A big integer.
@@ -12,8 +13,6 @@ pub type AscBigInt = AscTypedArray<u8>;
 #[derive(Clone)]
 #[repr(C)]
 pub struct AscBigDecimal {
-    // FIXME(nlordell): In theory, this is a reference to a big int. However,
-    // dealing with lifetimes of sharing references is not worth the effort.
     digits: AscBox<AscBigInt>,
     exp: AscBox<AscBigInt>,
 }
@@ -28,4 +27,35 @@ impl AscBigDecimal {
     pub fn digits(&self) -> &AscRef<AscBigInt> {
         self.digits.as_asc_ref()
     }
+
+    /// Gets the exponent.
+    pub fn exp(&self) -> &AscRef<AscBigInt> {
+        self.exp.as_asc_ref()
+    }
+}
+
+impl AscClass for AscBigDecimal {
+    const ID: u32 = TypeIndex::BigDecimal.class_id();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_decimal_clone_shares_digits() {
+        let value = AscBigDecimal::new(
+            AscBigInt::from_bytes(&[0x39, 0x30]),
+            AscBigInt::from_bytes(&[0xfe]),
+        );
+        let clone = AscBigDecimal::clone(value.as_asc_ref());
+
+        assert_eq!(clone.digits.as_ptr(), value.as_asc_ref().digits.as_ptr());
+        assert_eq!(clone.exp.as_ptr(), value.as_asc_ref().exp.as_ptr());
+
+        // The digits outlive the original value.
+        drop(value);
+        assert_eq!(clone.digits().as_slice(), [0x39, 0x30]);
+        assert_eq!(clone.exp.as_asc_ref().as_slice(), [0xfe]);
+    }
 }
@@ -1,6 +1,9 @@
 //! AssemblyScript string.
 
-use super::boxed::{AscBox, AscNullableBox, AscSlice};
+use super::{
+    boxed::{AscBox, AscNullableBox, AscSlice, HostValue},
+    class,
+};
 use std::{
     borrow::Borrow,
     fmt::{self, Debug, Formatter},
@@ -27,6 +30,15 @@ impl AscStr {
     }
 }
 
+// SAFETY: `AscStr` is a transparent wrapper around `AscSlice`.
+unsafe impl HostValue for AscStr {
+    const ALIGN: usize = AscSlice::<u16>::ALIGN;
+
+    fn is_valid_len(len: usize) -> bool {
+        AscSlice::<u16>::is_valid_len(len)
+    }
+}
+
 impl Debug for AscStr {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_tuple("AscStr")
@@ -56,7 +68,7 @@ impl AscString {
     /// Creates a new AssemblyScript string from a Rust string slice.
     pub fn new(s: &str) -> Self {
         let len = s.encode_utf16().count();
-        let inner = AscBox::with_len(len, s.encode_utf16());
+        let inner = AscBox::with_class_id(class::STRING, len, s.encode_utf16());
 
         Self { inner }
     }
@@ -106,6 +118,6 @@ impl AscNullableString {
     pub fn as_asc_str(&self) -> Option<&AscStr> {
         let slice = self.inner.as_asc_ref()?;
         // SAFETY: `AscStr` is a transparent wrapper around `AscSlice`.
-        Some(unsafe { mem::transmute(slice) })
+        Some(unsafe { mem::transmute::<&AscSlice<u16>, &AscStr>(slice) })
     }
 }
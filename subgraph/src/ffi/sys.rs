@@ -21,6 +21,9 @@ extern "C" {
         column_number: u32,
     ) -> !;
 
+    #[link_name = "arweave.transactionData"]
+    pub fn arweave__transaction_data(tx_id: *const AscStr) -> *const AscRef<AscBytes>;
+
     #[link_name = "bigDecimal.dividedBy"]
     pub fn big_decimal__divided_by(
         x: *const AscRef<AscBigDecimal>,
@@ -115,6 +118,8 @@ extern "C" {
         params: *const AscRef<AscArray<AscString>>,
         context: *const AscRef<AscEntity>,
     );
+    #[link_name = "dataSource.id"]
+    pub fn data_source__id() -> *const AscStr;
     #[link_name = "dataSource.network"]
     pub fn data_source__network() -> *const AscStr;
 
@@ -132,9 +137,13 @@ extern "C" {
     ) -> *const AscRef<AscEthereumValue>;
     #[link_name = "ethereum.encode"]
     pub fn ethereum__encode(value: *const AscRef<AscEthereumValue>) -> *const AscRef<AscBytes>;
+    #[link_name = "ethereum.getBalance"]
+    pub fn ethereum__get_balance(address: *const AscRef<AscAddress>) -> *const AscRef<AscBigInt>;
+    #[link_name = "ethereum.hasCode"]
+    pub fn ethereum__has_code(address: *const AscRef<AscAddress>) -> *const AscRef<bool>;
 
     #[link_name = "ipfs.cat"]
-    pub fn ipfs__cat(hash: *const AscStr) -> *const AscBytes;
+    pub fn ipfs__cat(hash: *const AscStr) -> *const AscRef<AscBytes>;
     #[link_name = "ipfs.map"]
     pub fn ipfs__map(
         hash: *const AscStr,
@@ -143,16 +152,24 @@ extern "C" {
         flags: *const AscRef<AscArray<AscString>>,
     );
 
+    // NOTE: JSON host functions are replaced by Rust implementations with the
+    // `native-json` feature.
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.fromBytes"]
     pub fn json__from_bytes(data: *const AscRef<AscBytes>) -> *const AscRef<AscJsonValue>;
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.toBigInt"]
     pub fn json__to_big_int(data: *const AscStr) -> *const AscRef<AscBigInt>;
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.toF64"]
     pub fn json__to_f64(data: *const AscStr) -> f64;
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.toI64"]
     pub fn json__to_i64(data: *const AscStr) -> i64;
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.toU64"]
     pub fn json__to_u64(data: *const AscStr) -> u64;
+    #[cfg_attr(feature = "native-json", allow(dead_code))]
     #[link_name = "json.try_fromBytes"]
     pub fn json__try_from_bytes(
         data: *const AscRef<AscBytes>,
@@ -163,6 +180,17 @@ extern "C" {
 
     #[link_name = "store.get"]
     pub fn store__get(entity: *const AscStr, id: *const AscStr) -> *const AscRef<AscEntity>;
+    #[link_name = "store.get_in_block"]
+    pub fn store__get_in_block(
+        entity: *const AscStr,
+        id: *const AscStr,
+    ) -> *const AscRef<AscEntity>;
+    #[link_name = "store.loadRelated"]
+    pub fn store__load_related(
+        entity: *const AscStr,
+        id: *const AscStr,
+        field: *const AscStr,
+    ) -> *const AscRef<AscArray<AscBox<AscEntity>>>;
     #[link_name = "store.remove"]
     pub fn store__remove(entity: *const AscStr, id: *const AscStr);
     #[link_name = "store.set"]
@@ -181,3 +209,34 @@ extern "C" {
     #[link_name = "typeConversion.stringToH160"]
     pub fn type_conversion__string_to_h160(bytes: *const AscStr) -> *const AscRef<AscUint8Array>;
 }
+
+/// Native stubs for host functions.
+///
+/// Host functions are not available in native unit tests. These stubs allow
+/// linking tests for code that references the host only on paths that the
+/// tests don't take, and abort if they are called anyway.
+#[cfg(all(test, not(target_arch = "wasm32")))]
+#[allow(non_snake_case)]
+mod stubs {
+    use super::*;
+
+    macro_rules! stubs {
+        ($($name:literal => fn $fn:ident($($t:ty),*) -> $r:ty;)*) => {$(
+            #[export_name = $name]
+            extern "C" fn $fn($(_: $t),*) -> $r {
+                // NOTE: Panics can't unwind out of `extern "C"` functions, so
+                // report the call and abort explicitly.
+                eprintln!("called host function {} in a native test", $name);
+                std::process::abort()
+            }
+        )*};
+    }
+
+    stubs! {
+        "bigDecimal.fromString" => fn big_decimal__from_string(*const AscStr) -> *const AscRef<AscBigDecimal>;
+        "bigDecimal.toString" => fn big_decimal__to_string(*const AscRef<AscBigDecimal>) -> *const AscStr;
+        "bigInt.fromString" => fn big_int__from_string(*const AscStr) -> *const AscRef<AscBigInt>;
+        "typeConversion.bigIntToHex" => fn type_conversion__big_int_to_hex(*const AscRef<AscBigInt>) -> *const AscStr;
+        "typeConversion.bigIntToString" => fn type_conversion__big_int_to_string(*const AscRef<AscBigInt>) -> *const AscStr;
+    }
+}
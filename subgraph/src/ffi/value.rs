@@ -2,6 +2,7 @@
 
 use super::{
     boxed::{AscBox, AscNullableBox, AscRef},
+    class::{AscClass, TypeIndex},
     num::{AscBigDecimal, AscBigInt},
     str::{AscStr, AscString},
     types::{AscAddress, AscBytes},
@@ -22,7 +23,10 @@ pub struct AscArray<T> {
 
 impl<T> AscArray<T> {
     /// Creates a new AssemblyScript array from the specificed vector.
-    pub fn new(items: Vec<T>) -> AscBox<Self> {
+    pub fn new(items: Vec<T>) -> AscBox<Self>
+    where
+        Self: AscClass,
+    {
         let length = items.len();
 
         let buffer = items.into_iter().collect::<AscBox<_>>();
@@ -46,23 +50,42 @@ impl<T> AscArray<T> {
     }
 }
 
-impl<T> Clone for AscArray<T>
-where
-    T: Clone,
-{
+impl<T> Clone for AscArray<T> {
     fn clone(&self) -> Self {
-        let buffer = self.buffer.clone();
-        let data_start = buffer.as_ptr().cast();
-
+        // NOTE: The buffer is shared, so the data pointer remains valid.
         Self {
-            buffer,
-            data_start,
+            buffer: self.buffer.clone(),
+            data_start: self.data_start,
             byte_length: self.byte_length,
             length: self.length,
         }
     }
 }
 
+impl AscClass for AscArray<AscString> {
+    const ID: u32 = TypeIndex::ArrayString.class_id();
+}
+
+impl AscClass for AscArray<AscBox<AscEntityValue>> {
+    const ID: u32 = TypeIndex::ArrayStoreValue.class_id();
+}
+
+impl AscClass for AscArray<AscBox<AscJsonValue>> {
+    const ID: u32 = TypeIndex::ArrayJsonValue.class_id();
+}
+
+impl AscClass for AscArray<AscBox<AscEthereumValue>> {
+    const ID: u32 = TypeIndex::ArrayEthereumValue.class_id();
+}
+
+impl AscClass for AscArray<AscBox<AscMapEntry<AscBox<AscEntityValue>>>> {
+    const ID: u32 = TypeIndex::ArrayTypedMapEntryStringStoreValue.class_id();
+}
+
+impl AscClass for AscArray<AscBox<AscMapEntry<AscBox<AscJsonValue>>>> {
+    const ID: u32 = TypeIndex::ArrayTypedMapEntryStringJsonValue.class_id();
+}
+
 /// A AssemblyScript map with string keys.
 #[repr(C)]
 pub struct AscMap<T> {
@@ -78,7 +101,11 @@ pub struct AscMapEntry<T> {
 
 impl<T> AscMap<T> {
     /// Returns entries as a slice.
-    pub fn new(entries: Vec<AscBox<AscMapEntry<T>>>) -> AscBox<Self> {
+    pub fn new(entries: Vec<AscBox<AscMapEntry<T>>>) -> AscBox<Self>
+    where
+        Self: AscClass,
+        AscArray<AscBox<AscMapEntry<T>>>: AscClass,
+    {
         AscBox::new(Self {
             entries: AscArray::new(entries),
         })
@@ -92,7 +119,10 @@ impl<T> AscMap<T> {
 
 impl<T> AscMapEntry<T> {
     /// Creates a new AssemblyScript map entry.
-    pub fn new(key: AscString, value: T) -> AscBox<Self> {
+    pub fn new(key: AscString, value: T) -> AscBox<Self>
+    where
+        Self: AscClass,
+    {
         AscBox::new(Self { key, value })
     }
 
@@ -107,13 +137,31 @@ impl<T> AscMapEntry<T> {
     }
 }
 
+impl AscClass for AscMap<AscBox<AscEntityValue>> {
+    const ID: u32 = TypeIndex::TypedMapStringStoreValue.class_id();
+}
+
+impl AscClass for AscMap<AscBox<AscJsonValue>> {
+    const ID: u32 = TypeIndex::TypedMapStringJsonValue.class_id();
+}
+
+impl AscClass for AscMapEntry<AscBox<AscEntityValue>> {
+    const ID: u32 = TypeIndex::TypedMapEntryStringStoreValue.class_id();
+}
+
+impl AscClass for AscMapEntry<AscBox<AscJsonValue>> {
+    const ID: u32 = TypeIndex::TypedMapEntryStringJsonValue.class_id();
+}
+
 /// An AssemblyScript result type.
+#[cfg_attr(feature = "native-json", allow(dead_code))]
 #[repr(C)]
 pub struct AscResult<T, E> {
     ok: AscNullableBox<T>,
     err: AscNullableBox<E>,
 }
 
+#[cfg_attr(feature = "native-json", allow(dead_code))]
 impl<T, E> AscResult<T, E> {
     /// Converst the AssemblyScript result wrapper into a Rust standard library
     /// [`Result`].
@@ -129,29 +177,41 @@ impl<T, E> AscResult<T, E> {
 /// Generate code for a tagged union.
 macro_rules! asc_tagged_union {
     (
+        @union
         $(#[$attr:meta])*
         $value:ident, $kind:ident, $payload:ident, $data:ident {$(
             $variant:ident , $field:ident ($($type:tt)*) = $tag:literal ,
-
         )*}
     ) => {
         $(#[$attr])*
         #[repr(C)]
         pub struct $value {
-            kind: $kind,
+            // NOTE: Keep the raw discriminant value, as the host may specify
+            // value kinds that we don't know about. Reading those into a Rust
+            // `enum` would be UB.
+            kind: u32,
             data: $payload,
         }
 
-        #[allow(clippy::manual_non_exhaustive, dead_code)]
         #[derive(Clone, Copy, Debug)]
-        #[non_exhaustive]
         #[repr(u32)]
         enum $kind {
             $(
                 $variant = $tag,
             )*
-            #[doc(hidden)]
-            _NonExhaustive,
+        }
+
+        impl $kind {
+            /// Returns the value kind for a raw discriminant, or `None` if the
+            /// discriminant is not known.
+            fn from_raw(kind: u32) -> Option<Self> {
+                match kind {
+                    $(
+                        $tag => Some(Self::$variant),
+                    )*
+                    _ => None,
+                }
+            }
         }
 
         #[repr(C)]
@@ -166,33 +226,22 @@ macro_rules! asc_tagged_union {
             $(
                 $variant(asc_tagged_union_field!(ref 'a: $($type)*)),
             )*
+            /// A value of a kind that is not known to this crate, with its
+            /// raw discriminant.
+            Unknown(u32),
         }
 
-        #[allow(dead_code, unused_variables)]
+        #[allow(dead_code)]
         impl $value {
-            $(
-                /// Creates a new value.
-                pub fn $field(
-                    value: asc_tagged_union_field!(owned: $($type)*),
-                ) -> AscBox<Self> {
-                    AscBox::new(Self {
-                        kind: $kind::$variant,
-                        data: $payload {
-                            $field: asc_tagged_union_field!(new(value): $($type)*),
-                        },
-                    })
-                }
-            )*
-
             /// Returns a reference to the inner data for this value.
-            pub fn data(&self) -> $data {
-                match self.kind {
+            pub fn data(&self) -> $data<'_> {
+                match $kind::from_raw(self.kind) {
                     $(
-                        $kind::$variant => $data::$variant(
+                        Some($kind::$variant) => $data::$variant(
                             asc_tagged_union_field!(data(self.data.$field): $($type)*),
                         ),
                     )*
-                    _ => panic!("unknown value kind {:#x}", self.kind as u32),
+                    None => $data::Unknown(self.kind),
                 }
             }
         }
@@ -202,16 +251,67 @@ macro_rules! asc_tagged_union {
                 // SAFETY: By construction, we are using the right union variant
                 // and we only ever drop when the container is dropping, meening
                 // the field will no longer be accessed.
-                match self.kind {
+                match $kind::from_raw(self.kind) {
                     $(
-                        $kind::$variant =>
+                        Some($kind::$variant) =>
                             asc_tagged_union_field!(drop(self.data.$field): $($type)*),
                     )*
-                    _ => ()
+                    None => (),
                 }
             }
         }
     };
+    // NOTE: Values with a class ID can be created by the module, while values
+    // without one can only be received from the host.
+    (
+        $(#[$attr:meta])*
+        $value:ident = $class:expr, $kind:ident, $payload:ident, $data:ident {$(
+            $variant:ident , $field:ident ($($type:tt)*) = $tag:literal ,
+        )*}
+    ) => {
+        asc_tagged_union! {
+            @union
+            $(#[$attr])*
+            $value, $kind, $payload, $data {$(
+                $variant, $field ($($type)*) = $tag,
+            )*}
+        }
+
+        impl AscClass for $value {
+            const ID: u32 = $class;
+        }
+
+        #[allow(dead_code, unused_variables)]
+        impl $value {
+            $(
+                /// Creates a new value.
+                pub fn $field(
+                    value: asc_tagged_union_field!(owned: $($type)*),
+                ) -> AscBox<Self> {
+                    AscBox::new(Self {
+                        kind: $kind::$variant as _,
+                        data: $payload {
+                            $field: asc_tagged_union_field!(new(value): $($type)*),
+                        },
+                    })
+                }
+            )*
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $value:ident, $kind:ident, $payload:ident, $data:ident {$(
+            $variant:ident , $field:ident ($($type:tt)*) = $tag:literal ,
+        )*}
+    ) => {
+        asc_tagged_union! {
+            @union
+            $(#[$attr])*
+            $value, $kind, $payload, $data {$(
+                $variant, $field ($($type)*) = $tag,
+            )*}
+        }
+    };
 }
 
 #[rustfmt::skip]
@@ -245,12 +345,14 @@ macro_rules! asc_tagged_union_field {
     (drop($f:expr): boxed $type:ty) => { unsafe { ManuallyDrop::drop(&mut $f) } };
 }
 
+pub(super) use {asc_tagged_union, asc_tagged_union_field};
+
 /// An AssemblyScript Subgraph key-value map.
 pub type AscEntity = AscMap<AscBox<AscEntityValue>>;
 
 asc_tagged_union! {
     /// An AssemblyScript JSON dynamic value.
-    AscEntityValue,
+    AscEntityValue = TypeIndex::StoreValue.class_id(),
     AscEntityValueKind,
     AscEntityValuePayload,
     AscEntityValueData {
@@ -262,12 +364,14 @@ asc_tagged_union! {
         Null, null (null) = 5,
         Bytes, bytes (boxed AscBytes) = 6,
         BigInt, bigint (boxed AscBigInt) = 7,
+        Int8, int8 (value i64) = 8,
+        Timestamp, timestamp (value i64) = 9,
     }
 }
 
 asc_tagged_union! {
     /// An AssemblyScript JSON dynamic value.
-    AscJsonValue,
+    AscJsonValue = TypeIndex::JsonValue.class_id(),
     AscJsonValueKind,
     AscJsonValuePayload,
     AscJsonValueData {
@@ -282,7 +386,7 @@ asc_tagged_union! {
 
 asc_tagged_union! {
     /// An AssemblyScript Ethereum dynamic value.
-    AscEthereumValue,
+    AscEthereumValue = TypeIndex::EthereumValue.class_id(),
     AscEthereumValueKind,
     AscEthereumValuePayload,
     AscEthereumValueData {
@@ -2,28 +2,32 @@
 
 use crate::{
     entity::Value,
-    ffi::{str::AscString, sys, value::AscArray},
+    ffi::{boxed::HostRef, str::AscString, sys, value::AscArray},
 };
 
 /// Gets an entity by name and ID.
 pub fn cat(hash: impl AsRef<str>) -> Option<Vec<u8>> {
     let hash = AscString::new(hash.as_ref());
-    let data = unsafe {
-        let data = sys::ipfs__cat(hash.as_ptr());
-        if data.is_null() {
-            return None;
-        }
-        &*data
-    };
+    let data = unsafe { HostRef::new_nullable(sys::ipfs__cat(hash.as_ptr()))?.into_ref() };
     Some(data.as_slice().to_owned())
 }
 
 /// Queues a callback for when an IPFS hash resolves.
-pub fn map(hash: impl AsRef<str>, callback: impl AsRef<str>, user_data: Value, flags: &[&str]) {
+///
+/// The callback is called for each value in the IPFS file, along with the
+/// specified user data. Callbacks are declared with [`ipfs_callback!`].
+///
+/// [`ipfs_callback!`]: crate::ipfs_callback
+pub fn map(hash: impl AsRef<str>, callback: Callback, user_data: Value, flags: &[Flag]) {
     let hash = AscString::new(hash.as_ref());
-    let callback = AscString::new(callback.as_ref());
+    let callback = AscString::new(callback.name);
     let user_data = user_data.to_raw();
-    let flags = AscArray::new(flags.iter().copied().map(AscString::new).collect());
+    let flags = AscArray::new(
+        flags
+            .iter()
+            .map(|flag| AscString::new(flag.as_str()))
+            .collect(),
+    );
     unsafe {
         sys::ipfs__map(
             hash.as_ptr(),
@@ -33,3 +37,122 @@ pub fn map(hash: impl AsRef<str>, callback: impl AsRef<str>, user_data: Value, f
         );
     }
 }
+
+/// Queues a callback for each JSON value of a JSON-lines IPFS file.
+pub fn map_json(hash: impl AsRef<str>, callback: Callback, user_data: impl Into<Value>) {
+    map(hash, callback, user_data.into(), &[Flag::Json]);
+}
+
+/// An IPFS map callback, declared with [`ipfs_callback!`].
+///
+/// [`ipfs_callback!`]: crate::ipfs_callback
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Callback {
+    name: &'static str,
+}
+
+impl Callback {
+    /// Creates a callback from the name of an exported function.
+    ///
+    /// This is useful for callbacks that are exported manually instead of with
+    /// [`ipfs_callback!`]. The function must take JSON and entity value
+    /// pointers, see [`json::Value::from_ptr`] and [`Value::from_ptr`].
+    ///
+    /// [`ipfs_callback!`]: crate::ipfs_callback
+    /// [`json::Value::from_ptr`]: crate::json::Value::from_ptr
+    pub const fn from_name(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// Returns the exported symbol name of the callback.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// A flag for [`map`]ping IPFS files.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Flag {
+    /// The IPFS file contains JSON values, one per line.
+    Json,
+}
+
+impl Flag {
+    /// Returns the flag as passed to the host.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Declares an IPFS map callback.
+///
+/// This exports the function to the Subgraph host, and declares a
+/// [`Callback`] constant with the same name that can be passed to [`map`] and
+/// [`map_json`].
+///
+/// ```
+/// use subgraph::{entity, ipfs, json};
+///
+/// subgraph::ipfs_callback! {
+///     fn process_item(value: json::Value, user_data: entity::Value) {
+///         // ...
+///     }
+/// }
+///
+/// fn handle(hash: &str) {
+///     ipfs::map_json(hash, process_item, entity::Value::Null);
+/// }
+/// ```
+#[macro_export]
+macro_rules! ipfs_callback {
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($value:ident : $value_ty:ty, $user_data:ident : $user_data_ty:ty $(,)?)
+        $body:block
+    ) => {
+        $(#[$attr])*
+        #[allow(non_upper_case_globals)]
+        $vis const $name: $crate::ipfs::Callback = {
+            #[export_name = ::std::stringify!($name)]
+            extern "C" fn callback(
+                value: $crate::json::ValuePtr,
+                user_data: $crate::entity::ValuePtr,
+            ) {
+                fn $name($value: $value_ty, $user_data: $user_data_ty) $body
+
+                // SAFETY: The host only calls exported IPFS callbacks with
+                // valid JSON and entity value pointers.
+                let (value, user_data) = unsafe {
+                    (
+                        $crate::json::Value::from_ptr(value),
+                        $crate::entity::Value::from_ptr(user_data),
+                    )
+                };
+                $name(value, user_data)
+            }
+
+            $crate::ipfs::Callback::from_name(::std::stringify!($name))
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entity, json};
+
+    crate::ipfs_callback! {
+        fn process_item(value: json::Value, user_data: entity::Value) {
+            let _ = (value, user_data);
+        }
+    }
+
+    #[test]
+    fn callback_names() {
+        assert_eq!(process_item.name(), "process_item");
+        assert_eq!(process_item, Callback::from_name("process_item"));
+    }
+}
@@ -1,14 +1,29 @@
 //! Subgraph JSON values.
 
+mod index;
+mod macros;
+#[cfg(feature = "native-json")]
+mod parse;
+#[cfg(feature = "serde")]
+mod serde;
+mod write;
+
+#[cfg(feature = "serde")]
+pub use self::serde::{from_bytes, from_value, SerdeError};
+pub use self::{
+    index::Index,
+    write::{to_string, to_string_pretty, to_vec},
+};
+pub use crate::conv::ValueError;
+#[cfg(not(feature = "native-json"))]
+use crate::ffi::{buf::AscTypedArray, str::AscString, sys};
 use crate::{
+    entity,
     ffi::{
-        boxed::AscRef,
-        buf::AscTypedArray,
-        str::AscString,
-        sys,
+        boxed::{AscRef, HostRef},
         value::{AscJsonValue, AscJsonValueData},
     },
-    num::BigInt,
+    num::{BigDecimal, BigInt},
 };
 use indexmap::IndexMap;
 use std::{
@@ -30,6 +45,9 @@ pub enum Value {
     Object(IndexMap<String, Value>),
 }
 
+/// A JSON value pointer for an IPFS callback.
+pub type ValuePtr = *const AscRef<AscJsonValue>;
+
 impl Value {
     /// Creates a new instance from a raw JSON value.
     fn from_raw(raw: &AscRef<AscJsonValue>) -> Self {
@@ -60,26 +78,66 @@ impl Value {
                     })
                     .collect(),
             ),
+            AscJsonValueData::Unknown(kind) => panic!("unknown JSON value kind {kind:#x}"),
         }
     }
 
+    /// Creates a JSON value from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into an IPFS callback.
+    pub unsafe fn from_ptr(ptr: ValuePtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+
     /// Parses a new JSON from from some bytes.
+    ///
+    /// # Panics
+    ///
+    /// Invalid JSON traps the handler. When the `native-json` feature is
+    /// enabled, this panics instead.
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Self {
-        let bytes = bytes.as_ref();
-        let array = AscTypedArray::from_bytes(bytes);
-        let raw = unsafe { &*sys::json__from_bytes(array.as_ptr()) };
+        #[cfg(feature = "native-json")]
+        {
+            parse::parse(bytes.as_ref()).expect("invalid JSON")
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let bytes = bytes.as_ref();
+            let array = AscTypedArray::from_bytes(bytes);
+            let raw = unsafe { HostRef::new(sys::json__from_bytes(array.as_ptr())).into_ref() };
 
-        Self::from_raw(raw)
+            Self::from_raw(raw)
+        }
     }
 
     /// Parses a new JSON value from bytes, returning and error on failure.
+    ///
+    /// When the `native-json` feature is enabled, JSON is parsed in Rust
+    /// instead of by the host, and parse errors include the line and column of
+    /// the error.
     pub fn try_from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, ParseError> {
-        let bytes = bytes.as_ref();
-        let array = AscTypedArray::from_bytes(bytes);
-        let result = unsafe { &*sys::json__try_from_bytes(array.as_ptr()) };
-        let raw = result.as_std_result().map_err(|_| ParseError)?.as_asc_ref();
+        #[cfg(feature = "native-json")]
+        {
+            parse::parse(bytes.as_ref())
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let bytes = bytes.as_ref();
+            let array = AscTypedArray::from_bytes(bytes);
+            let result =
+                unsafe { HostRef::new(sys::json__try_from_bytes(array.as_ptr())).into_ref() };
+            let raw = result
+                .as_std_result()
+                .map_err(|_| ParseError {
+                    kind: ParseErrorKind::Invalid,
+                    position: None,
+                })?
+                .as_asc_ref();
 
-        Ok(Self::from_raw(raw))
+            Ok(Self::from_raw(raw))
+        }
     }
 
     /// Returns the JSON value as a unit value, or `None` if the value is not
@@ -135,36 +193,164 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Returns the JSON value as a mutable vector of values, or `None` if the
+    /// value is not an array.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Self>> {
+        match self {
+            Self::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON value as a mutable map of values, or `None` if the
+    /// value is not an object.
+    pub fn as_object_mut(&mut self) -> Option<&mut IndexMap<String, Self>> {
+        match self {
+            Self::Object(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Takes the JSON value, leaving `null` in its place.
+    pub fn take(&mut self) -> Self {
+        std::mem::take(self)
+    }
+
+    /// Returns a human readable name of the kind of JSON value.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Array(_) => "array",
+            Self::Object(_) => "object",
+        }
+    }
 }
 
+/// Formats the JSON value as compact JSON, or as pretty-printed JSON with the
+/// alternate `{:#}` flag.
 impl Display for Value {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self {
-            Self::Null => f.write_str("null"),
-            Self::Bool(value) => write!(f, "{value}"),
-            Self::Number(value) => write!(f, "{value}"),
-            Self::String(value) => write!(f, "{value:?}"),
-            Self::Array(value) => {
-                f.write_str("[")?;
-                for (i, value) in value.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(",")?;
-                    }
-                    write!(f, "{value}")?;
-                }
-                f.write_str("]")
-            }
-            Self::Object(value) => {
-                f.write_str("{")?;
-                for (i, (key, value)) in value.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(",")?;
-                    }
-                    write!(f, "\"{key}\":{value}")?;
-                }
-                f.write_str("}")
+        write::write(f, self, f.alternate())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<Number> for Value {
+    fn from(value: Number) -> Self {
+        Self::Number(value)
+    }
+}
+
+macro_rules! impl_from_int_for_value {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                Self::Number(Number(Cow::Owned(value.to_string())))
             }
         }
+    )*};
+}
+
+impl_from_int_for_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Self::from(f64::from(value))
+    }
+}
+
+/// Converts a float to a JSON number. Non-finite floats cannot be represented
+/// in JSON, and are converted to `null`.
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        if value.is_finite() {
+            Self::Number(Number(Cow::Owned(value.to_string())))
+        } else {
+            Self::Null
+        }
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        Self::Number(Number(Cow::Owned(value.to_string())))
+    }
+}
+
+impl From<BigDecimal> for Value {
+    fn from(value: BigDecimal) -> Self {
+        Self::Number(Number(Cow::Owned(value.to_string())))
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl<T> From<Vec<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Vec<T>) -> Self {
+        Self::Array(value.into_iter().map(T::into).collect())
+    }
+}
+
+impl From<IndexMap<String, Value>> for Value {
+    fn from(value: IndexMap<String, Value>) -> Self {
+        Self::Object(value)
+    }
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map(T::into).unwrap_or_default()
+    }
+}
+
+/// Converts a JSON value into an entity value.
+///
+/// Integer numbers are converted to [`entity::Value::BigInt`] and all other
+/// numbers to [`entity::Value::BigDecimal`]. JSON objects cannot be stored in
+/// entities and fail to convert.
+impl TryFrom<Value> for entity::Value {
+    type Error = ValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(Self::Null),
+            Value::Bool(value) => Ok(Self::Bool(value)),
+            Value::Number(value) if value.is_integer() => Ok(Self::BigInt(value.to_big_int())),
+            Value::Number(value) => Ok(Self::BigDecimal(BigDecimal::parse(&value.0))),
+            Value::String(value) => Ok(Self::String(value)),
+            Value::Array(value) => Ok(Self::Array(
+                value
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            value => Err(ValueError::unexpected("entity compatible", value.kind())),
+        }
     }
 }
 
@@ -173,29 +359,84 @@ impl Display for Value {
 pub struct Number(Cow<'static, str>);
 
 impl Number {
+    /// Returns `true` if the number is an integer, that is it has no fraction
+    /// or exponent parts.
+    pub fn is_integer(&self) -> bool {
+        !self.0.contains(['.', 'e', 'E'])
+    }
+
     /// Converts this number to a [`BigInt`].
+    ///
+    /// # Panics
+    ///
+    /// Non-integer numbers trap the handler. When the `native-json` feature is
+    /// enabled, the conversion is done in Rust and panics instead.
     pub fn to_big_int(&self) -> BigInt {
-        let str = AscString::new(&self.0);
-        let raw = unsafe { &*sys::json__to_big_int(str.as_ptr()) };
-        BigInt::from_raw(raw)
+        #[cfg(feature = "native-json")]
+        {
+            BigInt::parse_decimal(&self.0).expect("JSON number is not an integer")
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let str = AscString::new(&self.0);
+            let raw = unsafe { HostRef::new(sys::json__to_big_int(str.as_ptr())).into_ref() };
+            BigInt::from_raw(raw)
+        }
     }
 
     /// Converts this number to a 64-bit float.
     pub fn to_f64(&self) -> f64 {
-        let str = AscString::new(&self.0);
-        unsafe { sys::json__to_f64(str.as_ptr()) }
+        #[cfg(feature = "native-json")]
+        {
+            self.0.parse().expect("JSON number is not a float")
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let str = AscString::new(&self.0);
+            unsafe { sys::json__to_f64(str.as_ptr()) }
+        }
     }
 
     /// Converts this number to a 64-bit signed integer.
+    ///
+    /// # Panics
+    ///
+    /// Numbers that are not 64-bit signed integers trap the handler. When the
+    /// `native-json` feature is enabled, the conversion is done in Rust and
+    /// panics instead.
     pub fn to_i64(&self) -> i64 {
-        let str = AscString::new(&self.0);
-        unsafe { sys::json__to_i64(str.as_ptr()) }
+        #[cfg(feature = "native-json")]
+        {
+            self.0
+                .parse()
+                .expect("JSON number is not a 64-bit signed integer")
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let str = AscString::new(&self.0);
+            unsafe { sys::json__to_i64(str.as_ptr()) }
+        }
     }
 
     /// Converts this number to a 64-bit un-signed integer.
+    ///
+    /// # Panics
+    ///
+    /// Numbers that are not 64-bit unsigned integers trap the handler. When the
+    /// `native-json` feature is enabled, the conversion is done in Rust and
+    /// panics instead.
     pub fn to_u64(&self) -> u64 {
-        let str = AscString::new(&self.0);
-        unsafe { sys::json__to_u64(str.as_ptr()) }
+        #[cfg(feature = "native-json")]
+        {
+            self.0
+                .parse()
+                .expect("JSON number is not a 64-bit unsigned integer")
+        }
+        #[cfg(not(feature = "native-json"))]
+        {
+            let str = AscString::new(&self.0);
+            unsafe { sys::json__to_u64(str.as_ptr()) }
+        }
     }
 }
 
@@ -226,13 +467,102 @@ impl FromStr for Value {
 }
 
 /// A JSON parse error.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
-pub struct ParseError;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    position: Option<(usize, usize)>,
+}
+
+impl ParseError {
+    /// Creates a parse error at the specified byte offset of the input.
+    #[cfg(feature = "native-json")]
+    fn at(kind: ParseErrorKind, input: &[u8], offset: usize) -> Self {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        // NOTE: Columns are counted in characters, so skip UTF-8 continuation
+        // bytes.
+        let column = before[line_start..]
+            .iter()
+            .filter(|&&b| b & 0xc0 != 0x80)
+            .count()
+            + 1;
+
+        Self {
+            kind,
+            position: Some((line, column)),
+        }
+    }
+
+    /// Returns the kind of parse error.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the 1-based line of the error, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.position.map(|(line, _)| line)
+    }
+
+    /// Returns the 1-based column, in characters, of the error, if known.
+    pub fn column(&self) -> Option<usize> {
+        self.position.map(|(_, column)| column)
+    }
+}
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.write_str("JSON parse error")
+        write!(f, "JSON parse error: {}", self.kind)?;
+        if let Some((line, column)) = self.position {
+            write!(f, " at line {line} column {column}")?;
+        }
+        Ok(())
     }
 }
 
 impl Error for ParseError {}
+
+/// The kind of JSON parse error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input is not valid JSON. This is reported by the host, which does
+    /// not provide any additional details.
+    Invalid,
+    /// The input ended unexpectedly.
+    UnexpectedEof,
+    /// An unexpected character was found.
+    UnexpectedCharacter(char),
+    /// A number is malformed.
+    InvalidNumber,
+    /// A string escape sequence is invalid.
+    InvalidEscape,
+    /// A string contains an unescaped control character.
+    ControlCharacter,
+    /// The input is not valid UTF-8.
+    InvalidUtf8,
+    /// There are non-whitespace characters after the JSON value.
+    TrailingCharacters,
+    /// Arrays and objects are nested too deeply.
+    RecursionLimit,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("invalid JSON"),
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character {c:?}"),
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::InvalidEscape => f.write_str("invalid escape sequence"),
+            Self::ControlCharacter => f.write_str("control character in string"),
+            Self::InvalidUtf8 => f.write_str("invalid UTF-8"),
+            Self::TrailingCharacters => f.write_str("trailing characters"),
+            Self::RecursionLimit => f.write_str("recursion limit exceeded"),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! JSON value indexing.
+
+use super::Value;
+use indexmap::IndexMap;
+use std::ops;
+
+/// A type that can be used to index into a JSON value.
+///
+/// This is implemented for `usize` for indexing into arrays and for strings
+/// for indexing into objects.
+pub trait Index: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+
+    #[doc(hidden)]
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value;
+}
+
+impl Index for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_array_mut()?.get_mut(*self)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        match value {
+            Value::Array(items) => {
+                let len = items.len();
+                items.get_mut(*self).unwrap_or_else(|| {
+                    panic!("cannot access index {self} of JSON array of length {len}")
+                })
+            }
+            _ => panic!("cannot access index {self} of JSON {}", value.kind()),
+        }
+    }
+}
+
+impl Index for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_object_mut()?.get_mut(self)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        if let Value::Null = value {
+            *value = Value::Object(IndexMap::new());
+        }
+        match value {
+            Value::Object(entries) => entries.entry(self.to_owned()).or_default(),
+            _ => panic!("cannot access key {self:?} of JSON {}", value.kind()),
+        }
+    }
+}
+
+impl Index for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        self.as_str().index_or_insert(value)
+    }
+}
+
+impl<T> Index for &T
+where
+    T: Index + ?Sized,
+{
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        (**self).index_or_insert(value)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T> Sealed for &T where T: Sealed + ?Sized {}
+}
+
+impl Value {
+    /// Returns the value at the specified array index or object key, or `None`
+    /// if it does not exist.
+    pub fn get(&self, index: impl Index) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    /// Returns a mutable reference to the value at the specified array index or
+    /// object key, or `None` if it does not exist.
+    pub fn get_mut(&mut self, index: impl Index) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    /// Looks up a value by JSON pointer, as specified by RFC 6901.
+    ///
+    /// For example, `/attributes/0/value` returns the `value` field of the
+    /// first item of the `attributes` array. An empty pointer returns the value
+    /// itself.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        tokens(pointer)?.try_fold(self, |value, token| match value {
+            Value::Array(items) => items.get(array_index(&token)?),
+            Value::Object(entries) => entries.get(&*token),
+            _ => None,
+        })
+    }
+
+    /// Looks up a value by JSON pointer, returning a mutable reference to it.
+    ///
+    /// See [`Value::pointer`] for more details.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        tokens(pointer)?.try_fold(self, |value, token| match value {
+            Value::Array(items) => items.get_mut(array_index(&token)?),
+            Value::Object(entries) => entries.get_mut(&*token),
+            _ => None,
+        })
+    }
+}
+
+/// Returns the unescaped reference tokens of a JSON pointer.
+fn tokens(pointer: &str) -> Option<impl Iterator<Item = String> + '_> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+    Some(
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|token| token.replace("~1", "/").replace("~0", "~")),
+    )
+}
+
+/// Parses an array index reference token, rejecting leading `0`s.
+fn array_index(token: &str) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl<I> ops::Index<I> for Value
+where
+    I: Index,
+{
+    type Output = Value;
+
+    /// Returns the value at the specified array index or object key.
+    ///
+    /// Returns `null` if the value does not exist.
+    fn index(&self, index: I) -> &Value {
+        static NULL: Value = Value::Null;
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+impl<I> ops::IndexMut<I> for Value
+where
+    I: Index,
+{
+    /// Returns a mutable reference to the value at the specified array index or
+    /// object key.
+    ///
+    /// Indexing a `null` value with a key turns it into an empty object, and
+    /// missing keys are inserted as `null`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array index is out of bounds, or if the value cannot be
+    /// indexed.
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        index.index_or_insert(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn pointer_lookup() {
+        let value = json!({
+            "a": [{ "b": 1 }, 2],
+            "c/d": 3,
+            "e~f": 4,
+            "~1": 5,
+            "": 6,
+        });
+
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/a/0/b"), Some(&json!(1)));
+        assert_eq!(value.pointer("/a/1"), Some(&json!(2)));
+        assert_eq!(value.pointer("/c~1d"), Some(&json!(3)));
+        assert_eq!(value.pointer("/e~0f"), Some(&json!(4)));
+        // `~01` unescapes to `~1` and not `/`.
+        assert_eq!(value.pointer("/~01"), Some(&json!(5)));
+        assert_eq!(value.pointer("/"), Some(&json!(6)));
+
+        assert_eq!(value.pointer("a"), None);
+        assert_eq!(value.pointer("/c/d"), None);
+        assert_eq!(value.pointer("/x"), None);
+        assert_eq!(value.pointer("/a/0/b/c"), None);
+    }
+
+    #[test]
+    fn pointer_array_indices() {
+        let mut value = json!([0, 1, 2]);
+
+        assert_eq!(value.pointer("/2"), Some(&json!(2)));
+        assert_eq!(value.pointer("/3"), None);
+        assert_eq!(value.pointer("/01"), None);
+        assert_eq!(value.pointer("/-"), None);
+        assert_eq!(value.pointer("/+1"), None);
+        assert_eq!(value.pointer("/99999999999999999999999"), None);
+
+        *value.pointer_mut("/1").unwrap() = json!("one");
+        assert_eq!(value, json!([0, "one", 2]));
+        assert_eq!(value.pointer_mut("/3"), None);
+    }
+
+    #[test]
+    fn index_lookup() {
+        let value = json!({ "a": [1, 2], "b": null });
+
+        assert_eq!(value["a"][1], json!(2));
+        assert_eq!(value["a"][2], Value::Null);
+        assert_eq!(value["x"]["y"], Value::Null);
+        assert_eq!(value[0], Value::Null);
+        assert_eq!(value.get("a").and_then(|a| a.get(5)), None);
+        assert_eq!(value.get(String::from("b")), Some(&Value::Null));
+    }
+
+    #[test]
+    fn index_insert() {
+        let mut value = Value::Null;
+        value["a"]["b"] = json!(1);
+        value["a"]["c"] = json!([0]);
+        value["a"]["c"][0] = json!(2);
+        assert_eq!(value, json!({ "a": { "b": 1, "c": [2] } }));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access index 1 of JSON array of length 1")]
+    fn index_out_of_range() {
+        let mut value = json!([0]);
+        value[1] = Value::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access key \"a\" of JSON array")]
+    fn index_key_of_array() {
+        let mut value = json!([0]);
+        value["a"] = Value::Null;
+    }
+}
@@ -0,0 +1,123 @@
+//! JSON value construction macro.
+
+/// Constructs a [`json::Value`](crate::json::Value) from a JSON-like literal.
+///
+/// Values can be `null`, nested arrays and objects, or any Rust expression
+/// that converts into a JSON value. Object keys can be string literals or
+/// parenthesized expressions converting into a `String`.
+///
+/// ```
+/// # use subgraph::json;
+/// let name = "Rusty";
+/// let value = json!({
+///     "name": name,
+///     "attributes": [
+///         { "trait_type": "rarity", "value": 42 },
+///     ],
+///     "image": null,
+/// });
+///
+/// assert_eq!(
+///     json::to_string(&value),
+///     r#"{"name":"Rusty","attributes":[{"trait_type":"rarity","value":42}],"image":null}"#,
+/// );
+/// ```
+#[macro_export]
+macro_rules! json {
+    // Array elements, accumulated into a vector.
+    (@array [$($items:expr,)*]) => {
+        ::std::vec![$($items,)*]
+    };
+    (@array [$($items:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($items,)* $crate::json!(null),] $($($rest)*)?)
+    };
+    (@array [$($items:expr,)*] [$($array:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($items,)* $crate::json!([$($array)*]),] $($($rest)*)?)
+    };
+    (@array [$($items:expr,)*] {$($object:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($items,)* $crate::json!({$($object)*}),] $($($rest)*)?)
+    };
+    (@array [$($items:expr,)*] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($items,)* $crate::json!($next),] $($($rest)*)?)
+    };
+
+    // Object entries, inserted into the map one at a time.
+    (@object $object:ident) => {};
+    (@object $object:ident $key:tt : null $(, $($rest:tt)*)?) => {
+        $object.insert(::std::string::String::from($key), $crate::json!(null));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : [$($array:tt)*] $(, $($rest:tt)*)?) => {
+        $object.insert(::std::string::String::from($key), $crate::json!([$($array)*]));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : {$($inner:tt)*} $(, $($rest:tt)*)?) => {
+        $object.insert(::std::string::String::from($key), $crate::json!({$($inner)*}));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $object.insert(::std::string::String::from($key), $crate::json!($value));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+
+    (null) => {
+        $crate::json::Value::Null
+    };
+    ([$($array:tt)*]) => {
+        $crate::json::Value::Array($crate::json!(@array [] $($array)*))
+    };
+    ({$($object:tt)*}) => {{
+        #[allow(unused_mut)]
+        let mut object = $crate::indexmap::IndexMap::new();
+        $crate::json!(@object object $($object)*);
+        $crate::json::Value::Object(object)
+    }};
+    ($other:expr) => {
+        $crate::json::Value::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::{self, Value};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn literals() {
+        assert_eq!(json!(null), Value::Null);
+        assert_eq!(json!(true), Value::Bool(true));
+        assert_eq!(json!("a"), Value::String("a".to_owned()));
+        assert_eq!(json!(-1).to_string(), "-1");
+        assert_eq!(json!(1.5).to_string(), "1.5");
+        assert_eq!(json!(f64::NAN), Value::Null);
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(json!([]), Value::Array(vec![]));
+        assert_eq!(
+            json!([null, [1, [2]], {}, "x",]).to_string(),
+            r#"[null,[1,[2]],{},"x"]"#,
+        );
+        let items = vec![1, 2];
+        assert_eq!(json!([items, 1 + 2]).to_string(), "[[1,2],3]");
+    }
+
+    #[test]
+    fn objects() {
+        assert_eq!(json!({}), Value::Object(IndexMap::new()));
+
+        let key = "dynamic";
+        let value = json!({
+            "a": null,
+            "b": [true, false],
+            "c": { "d": {} },
+            (key): 2 * 21,
+            (format!("{key}!")): "e",
+        });
+        assert_eq!(
+            json::to_string(&value),
+            r#"{"a":null,"b":[true,false],"c":{"d":{}},"dynamic":42,"dynamic!":"e"}"#,
+        );
+    }
+}
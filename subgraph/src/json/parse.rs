@@ -0,0 +1,461 @@
+//! Rust-native JSON parser.
+//!
+//! This parser is an alternative to the `json.fromBytes` host function, which
+//! traps the handler on invalid input, and works outside of a Subgraph host.
+
+use super::{Number, ParseError, ParseErrorKind, Value};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+
+/// The maximum nesting depth of arrays and objects.
+///
+/// This prevents malicious inputs from overflowing the stack, which is
+/// particularly small in WebAssembly.
+const MAX_DEPTH: usize = 128;
+
+/// Parses a JSON value from bytes.
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, ParseError> {
+    Parser {
+        input: bytes,
+        offset: 0,
+        depth: 0,
+    }
+    .document()
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn document(&mut self) -> Result<Value, ParseError> {
+        let value = self.value()?;
+        self.whitespace();
+        if self.offset < self.input.len() {
+            return Err(self.error(ParseErrorKind::TrailingCharacters));
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.whitespace();
+        match self.peek() {
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => Ok(Value::String(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => Ok(Value::Number(self.number()?)),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(_) => Err(self.unexpected()),
+            None => Err(self.error(ParseErrorKind::UnexpectedEof)),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Value, ParseError>,
+    ) -> Result<Value, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ParseErrorKind::RecursionLimit));
+        }
+        self.depth += 1;
+        let value = parse(self)?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn literal(&mut self, literal: &str, value: Value) -> Result<Value, ParseError> {
+        for expected in literal.bytes() {
+            match self.peek() {
+                Some(byte) if byte == expected => self.offset += 1,
+                Some(_) => return Err(self.unexpected()),
+                None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Number, ParseError> {
+        let start = self.offset;
+        self.eat(b'-');
+        match self.peek() {
+            Some(b'0') => self.offset += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.invalid_number()),
+        }
+        if self.eat(b'.') {
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.invalid_number());
+            }
+            self.digits();
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            let _ = self.eat(b'+') || self.eat(b'-');
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.invalid_number());
+            }
+            self.digits();
+        }
+
+        // SAFETY: Numbers only consist of ASCII characters.
+        let number = unsafe { std::str::from_utf8_unchecked(&self.input[start..self.offset]) };
+        Ok(Number(Cow::Owned(number.to_owned())))
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"')?;
+        let mut buffer = Vec::new();
+        loop {
+            let Some(byte) = self.peek() else {
+                return Err(self.error(ParseErrorKind::UnexpectedEof));
+            };
+            match byte {
+                b'"' => {
+                    self.offset += 1;
+                    break;
+                }
+                b'\\' => {
+                    self.offset += 1;
+                    self.escape(&mut buffer)?;
+                }
+                0x00..=0x1f => return Err(self.error(ParseErrorKind::ControlCharacter)),
+                0x20..=0x7f => {
+                    buffer.push(byte);
+                    self.offset += 1;
+                }
+                _ => {
+                    let c = self
+                        .char()
+                        .ok_or_else(|| self.error(ParseErrorKind::InvalidUtf8))?;
+                    buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    self.offset += c.len_utf8();
+                }
+            }
+        }
+
+        // SAFETY: The buffer is built from complete UTF-8 encoded characters.
+        Ok(unsafe { String::from_utf8_unchecked(buffer) })
+    }
+
+    fn escape(&mut self, buffer: &mut Vec<u8>) -> Result<(), ParseError> {
+        let Some(byte) = self.peek() else {
+            return Err(self.error(ParseErrorKind::UnexpectedEof));
+        };
+        let unescaped = match byte {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                self.offset += 1;
+                let start = self.offset - 2;
+                let high = self.hex4()?;
+                let code = match high {
+                    0xd800..=0xdbff => {
+                        if !(self.eat(b'\\') && self.eat(b'u')) {
+                            return Err(self.error_at(start, ParseErrorKind::InvalidEscape));
+                        }
+                        let low = self.hex4()?;
+                        if !(0xdc00..=0xdfff).contains(&low) {
+                            return Err(self.error_at(start, ParseErrorKind::InvalidEscape));
+                        }
+                        0x10000 + ((u32::from(high) - 0xd800) << 10) + (u32::from(low) - 0xdc00)
+                    }
+                    0xdc00..=0xdfff => {
+                        return Err(self.error_at(start, ParseErrorKind::InvalidEscape))
+                    }
+                    code => u32::from(code),
+                };
+                let c = char::from_u32(code)
+                    .ok_or_else(|| self.error_at(start, ParseErrorKind::InvalidEscape))?;
+                buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                return Ok(());
+            }
+            _ => return Err(self.error_at(self.offset - 1, ParseErrorKind::InvalidEscape)),
+        };
+        self.offset += 1;
+        buffer.push(unescaped as u8);
+        Ok(())
+    }
+
+    fn hex4(&mut self) -> Result<u16, ParseError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let Some(byte) = self.peek() else {
+                return Err(self.error(ParseErrorKind::UnexpectedEof));
+            };
+            let digit = (byte as char)
+                .to_digit(16)
+                .ok_or_else(|| self.error(ParseErrorKind::InvalidEscape))?;
+            value = (value << 4) | digit as u16;
+            self.offset += 1;
+        }
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.eat(b']') {
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.whitespace();
+            if self.eat(b']') {
+                return Ok(Value::Array(items));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'{')?;
+        let mut entries = IndexMap::new();
+        self.whitespace();
+        if self.eat(b'}') {
+            return Ok(Value::Object(entries));
+        }
+        loop {
+            self.whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.unexpected_or_eof());
+            }
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(b':')?;
+            let value = self.value()?;
+            entries.insert(key, value);
+            self.whitespace();
+            if self.eat(b'}') {
+                return Ok(Value::Object(entries));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.offset).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(self.unexpected_or_eof())
+        }
+    }
+
+    /// Decodes the UTF-8 character at the current offset.
+    fn char(&self) -> Option<char> {
+        let bytes = &self.input[self.offset..];
+        let bytes = &bytes[..bytes.len().min(4)];
+        let valid = match std::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(err) => std::str::from_utf8(&bytes[..err.valid_up_to()]).ok()?,
+        };
+        valid.chars().next()
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.char() {
+            Some(c) => self.error(ParseErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ParseErrorKind::InvalidUtf8),
+        }
+    }
+
+    fn unexpected_or_eof(&self) -> ParseError {
+        match self.peek() {
+            Some(_) => self.unexpected(),
+            None => self.error(ParseErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn invalid_number(&self) -> ParseError {
+        match self.peek() {
+            Some(_) => self.error(ParseErrorKind::InvalidNumber),
+            None => self.error(ParseErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        self.error_at(self.offset, kind)
+    }
+
+    fn error_at(&self, offset: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError::at(kind, self.input, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(s: &'static str) -> Value {
+        Value::Number(Number(Cow::Borrowed(s)))
+    }
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_owned())
+    }
+
+    fn error(input: impl AsRef<[u8]>) -> (ParseErrorKind, usize, usize) {
+        let err = parse(input.as_ref()).unwrap_err();
+        (err.kind(), err.line().unwrap(), err.column().unwrap())
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(
+            parse(br#" { "a": [null, true, false], "b": { "c": "d" }, "e": 1 } "#).unwrap(),
+            Value::Object(IndexMap::from([
+                (
+                    "a".to_owned(),
+                    Value::Array(vec![Value::Null, Value::Bool(true), Value::Bool(false)]),
+                ),
+                (
+                    "b".to_owned(),
+                    Value::Object(IndexMap::from([("c".to_owned(), string("d"))])),
+                ),
+                ("e".to_owned(), number("1")),
+            ])),
+        );
+        assert_eq!(parse(b"[]").unwrap(), Value::Array(vec![]));
+        assert_eq!(parse(b"{}").unwrap(), Value::Object(IndexMap::new()));
+    }
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(
+            parse(br#""\"\\\/\b\f\n\r\t""#).unwrap(),
+            string("\"\\/\u{8}\u{c}\n\r\t"),
+        );
+        assert_eq!(
+            parse(br#""\u0041\u00e9\u20ac""#).unwrap(),
+            string("A\u{e9}\u{20ac}")
+        );
+        assert_eq!(parse("\"é🦀\"".as_bytes()).unwrap(), string("é🦀"));
+    }
+
+    #[test]
+    fn parses_surrogate_pairs() {
+        assert_eq!(parse(br#""\ud83e\udd80""#).unwrap(), string("\u{1f980}"));
+        assert_eq!(parse(br#""\ud800\udc00""#).unwrap(), string("\u{10000}"));
+        assert_eq!(parse(br#""\udbff\udfff""#).unwrap(), string("\u{10ffff}"));
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        use ParseErrorKind::*;
+
+        // Lone and mismatched surrogates are reported at the escape start.
+        assert_eq!(error(br#""\ud83e""#), (InvalidEscape, 1, 2));
+        assert_eq!(error(br#""x\ud83eA""#), (InvalidEscape, 1, 3));
+        assert_eq!(error(br#""\udd80""#), (InvalidEscape, 1, 2));
+        assert_eq!(error(br#""\x""#), (InvalidEscape, 1, 2));
+        assert_eq!(error(br#""\u00g0""#), (InvalidEscape, 1, 6));
+        assert_eq!(error(br#""\u00"#), (UnexpectedEof, 1, 6));
+        assert_eq!(error(b"\"a\nb\""), (ControlCharacter, 1, 3));
+        assert_eq!(error(b"\"a\xffb\""), (InvalidUtf8, 1, 3));
+        assert_eq!(error(b"\"abc"), (UnexpectedEof, 1, 5));
+    }
+
+    #[test]
+    fn parses_numbers() {
+        for s in [
+            "0", "-0", "7", "-12", "1.5", "-0.25", "1e10", "1E+2", "2e-3", "-1.5e10",
+        ] {
+            assert_eq!(parse(s.as_bytes()).unwrap(), number(s));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        use ParseErrorKind::*;
+
+        assert_eq!(error("01"), (TrailingCharacters, 1, 2));
+        assert_eq!(error("-"), (UnexpectedEof, 1, 2));
+        assert_eq!(error("-a"), (InvalidNumber, 1, 2));
+        assert_eq!(error("1."), (UnexpectedEof, 1, 3));
+        assert_eq!(error("1.e1"), (InvalidNumber, 1, 3));
+        assert_eq!(error("1e"), (UnexpectedEof, 1, 3));
+        assert_eq!(error("1e+"), (UnexpectedEof, 1, 4));
+        assert_eq!(error("1ex"), (InvalidNumber, 1, 3));
+        assert_eq!(error("+1"), (UnexpectedCharacter('+'), 1, 1));
+        assert_eq!(error(".5"), (UnexpectedCharacter('.'), 1, 1));
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(nested(MAX_DEPTH).as_bytes()).is_ok());
+        assert_eq!(
+            error(nested(MAX_DEPTH + 1)),
+            (ParseErrorKind::RecursionLimit, 1, MAX_DEPTH + 1),
+        );
+
+        let nested = format!(
+            "{}1{}",
+            r#"{"a":"#.repeat(MAX_DEPTH + 1),
+            "}".repeat(MAX_DEPTH + 1)
+        );
+        assert_eq!(error(nested).0, ParseErrorKind::RecursionLimit);
+    }
+
+    #[test]
+    fn rejects_trailing_characters() {
+        use ParseErrorKind::*;
+
+        assert_eq!(error("null x"), (TrailingCharacters, 1, 6));
+        assert_eq!(error("{} {}"), (TrailingCharacters, 1, 4));
+        assert_eq!(error("[1]\n]"), (TrailingCharacters, 2, 1));
+        assert!(parse(b" true \r\n\t").is_ok());
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        use ParseErrorKind::*;
+
+        assert_eq!(error(""), (UnexpectedEof, 1, 1));
+        assert_eq!(
+            error("{\n  \"a\": tru\n}"),
+            (UnexpectedCharacter('\n'), 2, 11)
+        );
+        assert_eq!(error("[\"é\", x]"), (UnexpectedCharacter('x'), 1, 7));
+        assert_eq!(error("[1,]"), (UnexpectedCharacter(']'), 1, 4));
+        assert_eq!(error("[1 2]"), (UnexpectedCharacter('2'), 1, 4));
+        assert_eq!(error("{\"a\":1,}"), (UnexpectedCharacter('}'), 1, 8));
+        assert_eq!(error("{1:2}"), (UnexpectedCharacter('1'), 1, 2));
+        assert_eq!(error("{\"a\" 1}"), (UnexpectedCharacter('1'), 1, 6));
+        assert_eq!(error("[1,\n2"), (UnexpectedEof, 2, 2));
+    }
+}
@@ -0,0 +1,441 @@
+//! Serde support for deserializing Rust types from JSON values.
+
+use super::{Number, Value};
+use crate::num::serde::{BIG_DECIMAL_TOKEN, BIG_INT_TOKEN};
+use indexmap::map;
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, StringDeserializer},
+        DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+        Unexpected, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    vec,
+};
+
+/// Parses JSON bytes and deserializes them into a Rust value.
+pub fn from_bytes<T>(bytes: impl AsRef<[u8]>) -> Result<T, SerdeError>
+where
+    T: DeserializeOwned,
+{
+    let value = Value::try_from_bytes(bytes).map_err(de::Error::custom)?;
+    from_value(value)
+}
+
+/// Deserializes a JSON value into a Rust value.
+///
+/// JSON numbers are arbitrary-precision, and can be deserialized into
+/// [`BigInt`](crate::num::BigInt) and [`BigDecimal`](crate::num::BigDecimal)
+/// values without loss of precision.
+pub fn from_value<T>(value: Value) -> Result<T, SerdeError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+/// An error deserializing a Rust value from JSON.
+///
+/// The error includes the path to the JSON value that failed to deserialize.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SerdeError {
+    message: String,
+    /// Path segments to the failing value, innermost segment first.
+    path: Vec<PathSegment>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+impl SerdeError {
+    /// Returns the path to the JSON value that failed to deserialize, for
+    /// example `$.attributes[0].value`.
+    pub fn path(&self) -> String {
+        let mut path = String::from("$");
+        for segment in self.path.iter().rev() {
+            match segment {
+                PathSegment::Index(index) => path.push_str(&format!("[{index}]")),
+                PathSegment::Key(key)
+                    if !key.is_empty()
+                        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+                {
+                    path.push('.');
+                    path.push_str(key);
+                }
+                PathSegment::Key(key) => path.push_str(&format!("[{key:?}]")),
+            }
+        }
+        path
+    }
+
+    /// Returns the error message, without the path.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn at(mut self, segment: PathSegment) -> Self {
+        self.path.push(segment);
+        self
+    }
+}
+
+impl Display for SerdeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.path())
+    }
+}
+
+impl Error for SerdeError {}
+
+impl de::Error for SerdeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self {
+            message: msg.to_string(),
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(value),
+            Value::Number(value) => visit_number(value, visitor),
+            Value::String(value) => visitor.visit_string(value),
+            Value::Array(value) => visitor.visit_seq(ArrayAccess {
+                iter: value.into_iter(),
+                index: 0,
+            }),
+            Value::Object(value) => visitor.visit_map(ObjectAccess {
+                iter: value.into_iter(),
+                entry: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (name, self) {
+            // NOTE: Pass numbers as strings to big numbers, so that they can be
+            // deserialized without any loss of precision.
+            (BIG_INT_TOKEN | BIG_DECIMAL_TOKEN, Value::Number(value)) => {
+                visitor.visit_str(&value.0)
+            }
+            (BIG_INT_TOKEN | BIG_DECIMAL_TOKEN, value) => value.deserialize_any(visitor),
+            (_, value) => visitor.visit_newtype_struct(value),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(variant) => {
+                visitor.visit_enum(StringDeserializer::<SerdeError>::new(variant))
+            }
+            Value::Object(value) if value.len() == 1 => {
+                visitor.visit_enum(MapAccessDeserializer::new(ObjectAccess {
+                    iter: value.into_iter(),
+                    entry: None,
+                }))
+            }
+            value => Err(de::Error::invalid_type(
+                Unexpected::Other(value.kind()),
+                &"string or single key object enum",
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Visits a JSON number with the narrowest fitting primitive type.
+fn visit_number<'de, V>(number: Number, visitor: V) -> Result<V::Value, SerdeError>
+where
+    V: Visitor<'de>,
+{
+    let s = &*number.0;
+    if number.is_integer() {
+        if let Ok(value) = s.parse() {
+            return visitor.visit_i64(value);
+        } else if let Ok(value) = s.parse() {
+            return visitor.visit_u64(value);
+        } else if let Ok(value) = s.parse() {
+            return visitor.visit_i128(value);
+        } else if let Ok(value) = s.parse() {
+            return visitor.visit_u128(value);
+        }
+    }
+    match s.parse() {
+        Ok(value) => visitor.visit_f64(value),
+        Err(_) => Err(de::Error::invalid_value(Unexpected::Str(s), &"a number")),
+    }
+}
+
+struct ArrayAccess {
+    iter: vec::IntoIter<Value>,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> for ArrayAccess {
+    type Error = SerdeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let Some(value) = self.iter.next() else {
+            return Ok(None);
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(value)
+            .map(Some)
+            .map_err(|err| err.at(PathSegment::Index(index)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct ObjectAccess {
+    iter: map::IntoIter<String, Value>,
+    entry: Option<(String, Value)>,
+}
+
+impl<'de> MapAccess<'de> for ObjectAccess {
+    type Error = SerdeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        let result = seed
+            .deserialize(StringDeserializer::<SerdeError>::new(key.clone()))
+            .map(Some)
+            .map_err(|err| err.at(PathSegment::Key(key.clone())));
+        self.entry = Some((key, value));
+        result
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (key, value) = self
+            .entry
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(value)
+            .map_err(|err| err.at(PathSegment::Key(key)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, num::BigInt};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    enum Rarity {
+        Common,
+        Rare { level: u8 },
+    }
+
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    struct Attribute {
+        trait_type: String,
+        value: Option<u32>,
+    }
+
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    struct Metadata {
+        name: String,
+        #[serde(rename = "external-url")]
+        external_url: Option<String>,
+        attributes: Vec<Attribute>,
+        rarity: Rarity,
+    }
+
+    #[test]
+    fn deserialize_values() {
+        let value = json!({
+            "name": "Rusty",
+            "attributes": [
+                { "trait_type": "speed", "value": 42 },
+                { "trait_type": "shine", "value": null },
+            ],
+            "rarity": { "rare": { "level": 3 } },
+        });
+
+        assert_eq!(
+            from_value::<Metadata>(value).unwrap(),
+            Metadata {
+                name: "Rusty".to_owned(),
+                external_url: None,
+                attributes: vec![
+                    Attribute {
+                        trait_type: "speed".to_owned(),
+                        value: Some(42),
+                    },
+                    Attribute {
+                        trait_type: "shine".to_owned(),
+                        value: None,
+                    },
+                ],
+                rarity: Rarity::Rare { level: 3 },
+            },
+        );
+
+        assert_eq!(
+            from_value::<Rarity>(json!("common")).unwrap(),
+            Rarity::Common
+        );
+        assert_eq!(
+            from_value::<(bool, f64, i128)>(json!([
+                true,
+                1.5,
+                -170141183460469231731687303715884105728_i128
+            ]))
+            .unwrap(),
+            (true, 1.5, i128::MIN),
+        );
+    }
+
+    #[test]
+    fn deserialize_big_integers() {
+        let value = json!([u128::MAX, -1]);
+        let [max, neg] = from_value::<[BigInt; 2]>(value).unwrap();
+        assert_eq!(u128::try_from(&max), Ok(u128::MAX));
+        assert_eq!(i8::try_from(&neg), Ok(-1));
+    }
+
+    #[test]
+    fn error_paths() {
+        #[derive(Debug, Deserialize)]
+        struct B {
+            #[allow(dead_code)]
+            b: Vec<u8>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct A {
+            #[allow(dead_code)]
+            a: B,
+        }
+
+        let err = from_value::<A>(json!({ "a": { "b": [1, 2, 256] } })).unwrap_err();
+        assert_eq!(err.path(), "$.a.b[2]");
+        assert_eq!(err.message(), "invalid value: integer `256`, expected u8",);
+        assert_eq!(
+            err.to_string(),
+            "invalid value: integer `256`, expected u8 at $.a.b[2]",
+        );
+
+        let err = from_value::<Metadata>(json!({
+            "name": "Rusty",
+            "attributes": [{ "trait_type": "speed", "value": "fast" }],
+            "rarity": "common",
+        }))
+        .unwrap_err();
+        assert_eq!(err.path(), "$.attributes[0].value");
+
+        let err = from_value::<Metadata>(json!({
+            "name": "Rusty",
+            "external-url": 1,
+            "attributes": [],
+            "rarity": "common",
+        }))
+        .unwrap_err();
+        assert_eq!(err.path(), r#"$["external-url"]"#);
+
+        let err =
+            from_value::<Metadata>(json!({ "name": "Rusty", "rarity": "common" })).unwrap_err();
+        assert_eq!(err.path(), "$");
+        assert_eq!(err.message(), "missing field `attributes`");
+
+        let err = from_value::<Rarity>(json!(["common"])).unwrap_err();
+        assert_eq!(err.path(), "$");
+        assert_eq!(
+            err.message(),
+            "invalid type: array, expected string or single key object enum",
+        );
+    }
+
+    #[cfg(feature = "native-json")]
+    #[test]
+    fn deserialize_bytes() {
+        let attribute = from_bytes::<Attribute>(br#"{"trait_type":"speed","value":42}"#);
+        assert_eq!(
+            attribute.unwrap(),
+            Attribute {
+                trait_type: "speed".to_owned(),
+                value: Some(42),
+            },
+        );
+
+        let err = from_bytes::<Attribute>(b"{").unwrap_err();
+        assert_eq!(err.path(), "$");
+    }
+}
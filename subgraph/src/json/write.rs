@@ -0,0 +1,147 @@
+//! JSON serialization.
+
+use super::Value;
+use std::fmt::{self, Write};
+
+/// Serializes a JSON value to a compact string.
+pub fn to_string(value: &Value) -> String {
+    value.to_string()
+}
+
+/// Serializes a JSON value to a pretty-printed string, indented with two
+/// spaces.
+pub fn to_string_pretty(value: &Value) -> String {
+    format!("{value:#}")
+}
+
+/// Serializes a JSON value to compact UTF-8 bytes.
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    to_string(value).into_bytes()
+}
+
+/// Writes a JSON value, optionally pretty-printing it.
+pub(super) fn write(out: &mut impl Write, value: &Value, pretty: bool) -> fmt::Result {
+    Writer { out, pretty }.value(value, 0)
+}
+
+struct Writer<'a, W> {
+    out: &'a mut W,
+    pretty: bool,
+}
+
+impl<W> Writer<'_, W>
+where
+    W: Write,
+{
+    fn value(&mut self, value: &Value, depth: usize) -> fmt::Result {
+        match value {
+            Value::Null => self.out.write_str("null"),
+            Value::Bool(value) => write!(self.out, "{value}"),
+            Value::Number(value) => write!(self.out, "{value}"),
+            Value::String(value) => self.string(value),
+            Value::Array(items) => {
+                if items.is_empty() {
+                    return self.out.write_str("[]");
+                }
+                self.out.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.out.write_char(',')?;
+                    }
+                    self.newline(depth + 1)?;
+                    self.value(item, depth + 1)?;
+                }
+                self.newline(depth)?;
+                self.out.write_char(']')
+            }
+            Value::Object(entries) => {
+                if entries.is_empty() {
+                    return self.out.write_str("{}");
+                }
+                self.out.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        self.out.write_char(',')?;
+                    }
+                    self.newline(depth + 1)?;
+                    self.string(key)?;
+                    self.out.write_str(if self.pretty { ": " } else { ":" })?;
+                    self.value(value, depth + 1)?;
+                }
+                self.newline(depth)?;
+                self.out.write_char('}')
+            }
+        }
+    }
+
+    fn newline(&mut self, depth: usize) -> fmt::Result {
+        if self.pretty {
+            self.out.write_char('\n')?;
+            for _ in 0..depth {
+                self.out.write_str("  ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn string(&mut self, value: &str) -> fmt::Result {
+        self.out.write_char('"')?;
+        let mut start = 0;
+        for (i, c) in value.char_indices() {
+            let escape = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{8}' => "\\b",
+                '\u{c}' => "\\f",
+                '\0'..='\u{1f}' => "",
+                _ => continue,
+            };
+            self.out.write_str(&value[start..i])?;
+            match escape {
+                "" => write!(self.out, "\\u{:04x}", c as u32)?,
+                escape => self.out.write_str(escape)?,
+            }
+            start = i + c.len_utf8();
+        }
+        self.out.write_str(&value[start..])?;
+        self.out.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(
+            to_string(&json!("quote\" backslash\\ slash/")),
+            r#""quote\" backslash\\ slash/""#,
+        );
+        assert_eq!(to_string(&json!("\n\r\t\u{8}\u{c}")), r#""\n\r\t\b\f""#,);
+        assert_eq!(
+            to_string(&json!("\0\u{1}\u{1f} \u{7f}")),
+            "\"\\u0000\\u0001\\u001f \u{7f}\"",
+        );
+        assert_eq!(to_string(&json!("é🦀")), "\"é🦀\"");
+        assert_eq!(
+            to_string(&json!({ "k\"ey": "v\nalue" })),
+            r#"{"k\"ey":"v\nalue"}"#,
+        );
+    }
+
+    #[test]
+    fn pretty_prints() {
+        let value = json!({ "a": [1, {}], "b": [], "c": { "d": null } });
+        assert_eq!(to_string(&value), r#"{"a":[1,{}],"b":[],"c":{"d":null}}"#);
+        assert_eq!(
+            to_string_pretty(&value),
+            "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": [],\n  \"c\": {\n    \"d\": null\n  }\n}",
+        );
+        assert_eq!(to_vec(&json!([1])), b"[1]");
+    }
+}
@@ -4,7 +4,13 @@
 //! modules, enabling Subgraphs to be written in Rust 🦀.
 
 pub mod address;
+#[cfg(feature = "allocator")]
+mod allocator;
+pub mod arweave;
+pub mod bytes;
 pub mod conv;
+#[cfg(feature = "cosmos")]
+pub mod cosmos;
 pub mod crypto;
 pub mod datasource;
 pub mod ens;
@@ -15,7 +21,9 @@ mod ffi;
 pub mod ipfs;
 pub mod json;
 pub mod log;
+pub mod near;
 pub mod num;
 pub mod store;
+pub mod substreams;
 
 pub use indexmap;
@@ -0,0 +1,565 @@
+//! NEAR block and receipt handler types.
+
+use crate::{
+    crypto::Hash,
+    ffi::{
+        boxed::{AscRef, HostRef},
+        near::{
+            AscAccessKey, AscAccessKeyPermission, AscAccessKeyPermissionData, AscAction,
+            AscActionData, AscActionReceipt, AscBlock, AscBlockHeader, AscChunkHeader,
+            AscDataReceiver, AscExecutionOutcome, AscMerklePathItem, AscPublicKey,
+            AscReceiptWithOutcome, AscSignature, AscSlashedValidator, AscSuccessStatus,
+            AscSuccessStatusData, AscValidatorStake,
+        },
+        str::AscStr,
+    },
+    num::BigInt,
+};
+
+/// A NEAR block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    pub author: String,
+    pub header: BlockHeader,
+    pub chunks: Vec<ChunkHeader>,
+}
+
+/// A pointer to a NEAR block passed into a block handler.
+pub type BlockPtr = *const AscRef<AscBlock>;
+
+impl Block {
+    fn from_raw(b: &'static AscRef<AscBlock>) -> Self {
+        Self {
+            author: b.author().to_string_lossy(),
+            header: BlockHeader::from_raw(b.header()),
+            chunks: b
+                .chunks()
+                .as_slice()
+                .iter()
+                .map(|c| ChunkHeader::from_raw(c.as_asc_ref()))
+                .collect(),
+        }
+    }
+
+    /// Creates a block from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into a NEAR block handler.
+    pub unsafe fn from_ptr(ptr: BlockPtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+}
+
+/// A NEAR block header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockHeader {
+    pub height: u64,
+    pub prev_height: u64,
+    pub epoch_id: Hash,
+    pub next_epoch_id: Hash,
+    pub hash: Hash,
+    pub prev_hash: Hash,
+    pub prev_state_root: Hash,
+    pub chunk_receipts_root: Hash,
+    pub chunk_headers_root: Hash,
+    pub chunk_tx_root: Hash,
+    pub outcome_root: Hash,
+    pub challenges_root: Hash,
+    pub random_value: Hash,
+    pub validator_proposals: Vec<ValidatorStake>,
+    pub chunk_mask: Vec<bool>,
+    pub gas_price: BigInt,
+    pub block_ordinal: u64,
+    pub total_supply: BigInt,
+    pub challenges_result: Vec<SlashedValidator>,
+    pub last_final_block: Hash,
+    pub last_ds_final_block: Hash,
+    pub next_bp_hash: Hash,
+    pub block_merkle_root: Hash,
+    pub epoch_sync_data_hash: Vec<u8>,
+    pub approvals: Vec<Signature>,
+    pub signature: Signature,
+    pub latest_protocol_version: u32,
+}
+
+impl BlockHeader {
+    fn from_raw(h: &'static AscRef<AscBlockHeader>) -> Self {
+        Self {
+            height: h.height(),
+            prev_height: h.prev_height(),
+            epoch_id: Hash::from_raw(h.epoch_id()),
+            next_epoch_id: Hash::from_raw(h.next_epoch_id()),
+            hash: Hash::from_raw(h.hash()),
+            prev_hash: Hash::from_raw(h.prev_hash()),
+            prev_state_root: Hash::from_raw(h.prev_state_root()),
+            chunk_receipts_root: Hash::from_raw(h.chunk_receipts_root()),
+            chunk_headers_root: Hash::from_raw(h.chunk_headers_root()),
+            chunk_tx_root: Hash::from_raw(h.chunk_tx_root()),
+            outcome_root: Hash::from_raw(h.outcome_root()),
+            challenges_root: Hash::from_raw(h.challenges_root()),
+            random_value: Hash::from_raw(h.random_value()),
+            validator_proposals: h
+                .validator_proposals()
+                .as_slice()
+                .iter()
+                .map(|v| ValidatorStake::from_raw(v.as_asc_ref()))
+                .collect(),
+            chunk_mask: h.chunk_mask().as_slice().to_owned(),
+            gas_price: BigInt::from_raw(h.gas_price()),
+            block_ordinal: h.block_ordinal(),
+            total_supply: BigInt::from_raw(h.total_supply()),
+            challenges_result: h
+                .challenges_result()
+                .as_slice()
+                .iter()
+                .map(|s| SlashedValidator::from_raw(s.as_asc_ref()))
+                .collect(),
+            last_final_block: Hash::from_raw(h.last_final_block()),
+            last_ds_final_block: Hash::from_raw(h.last_ds_final_block()),
+            next_bp_hash: Hash::from_raw(h.next_bp_hash()),
+            block_merkle_root: Hash::from_raw(h.block_merkle_root()),
+            epoch_sync_data_hash: h.epoch_sync_data_hash().as_slice().to_owned(),
+            approvals: h
+                .approvals()
+                .as_slice()
+                .iter()
+                .map(|s| Signature::from_raw(s.as_asc_ref()))
+                .collect(),
+            signature: Signature::from_raw(h.signature()),
+            latest_protocol_version: h.latest_protocol_version(),
+        }
+    }
+}
+
+/// A NEAR chunk header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChunkHeader {
+    pub encoded_length: u64,
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    pub shard_id: u64,
+    pub height_created: u64,
+    pub height_included: u64,
+    pub chunk_hash: Hash,
+    pub signature: Signature,
+    pub prev_block_hash: Hash,
+    pub prev_state_root: Hash,
+    pub encoded_merkle_root: Hash,
+    pub balance_burnt: BigInt,
+    pub outgoing_receipts_root: Hash,
+    pub tx_root: Hash,
+    pub validator_proposals: Vec<ValidatorStake>,
+}
+
+impl ChunkHeader {
+    fn from_raw(c: &'static AscRef<AscChunkHeader>) -> Self {
+        Self {
+            encoded_length: c.encoded_length(),
+            gas_used: c.gas_used(),
+            gas_limit: c.gas_limit(),
+            shard_id: c.shard_id(),
+            height_created: c.height_created(),
+            height_included: c.height_included(),
+            chunk_hash: Hash::from_raw(c.chunk_hash()),
+            signature: Signature::from_raw(c.signature()),
+            prev_block_hash: Hash::from_raw(c.prev_block_hash()),
+            prev_state_root: Hash::from_raw(c.prev_state_root()),
+            encoded_merkle_root: Hash::from_raw(c.encoded_merkle_root()),
+            balance_burnt: BigInt::from_raw(c.balance_burnt()),
+            outgoing_receipts_root: Hash::from_raw(c.outgoing_receipts_root()),
+            tx_root: Hash::from_raw(c.tx_root()),
+            validator_proposals: c
+                .validator_proposals()
+                .as_slice()
+                .iter()
+                .map(|v| ValidatorStake::from_raw(v.as_asc_ref()))
+                .collect(),
+        }
+    }
+}
+
+/// A NEAR key type.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KeyType {
+    Ed25519,
+    Secp256k1,
+    /// A key type that is not known to this crate.
+    Unknown(i32),
+}
+
+impl KeyType {
+    fn from_raw(kind: i32) -> Self {
+        match kind {
+            0 => Self::Ed25519,
+            1 => Self::Secp256k1,
+            kind => Self::Unknown(kind),
+        }
+    }
+}
+
+/// A NEAR public key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublicKey {
+    pub kind: KeyType,
+    pub bytes: Vec<u8>,
+}
+
+impl PublicKey {
+    fn from_raw(k: &'static AscRef<AscPublicKey>) -> Self {
+        Self {
+            kind: KeyType::from_raw(k.kind()),
+            bytes: k.bytes().as_slice().to_owned(),
+        }
+    }
+}
+
+/// A NEAR signature.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Signature {
+    pub kind: KeyType,
+    pub bytes: Vec<u8>,
+}
+
+impl Signature {
+    fn from_raw(s: &'static AscRef<AscSignature>) -> Self {
+        Self {
+            kind: KeyType::from_raw(s.kind()),
+            bytes: s.bytes().as_slice().to_owned(),
+        }
+    }
+}
+
+/// A NEAR validator stake.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidatorStake {
+    pub account_id: String,
+    pub public_key: PublicKey,
+    pub stake: BigInt,
+}
+
+impl ValidatorStake {
+    fn from_raw(v: &'static AscRef<AscValidatorStake>) -> Self {
+        Self {
+            account_id: v.account_id().to_string_lossy(),
+            public_key: PublicKey::from_raw(v.public_key()),
+            stake: BigInt::from_raw(v.stake()),
+        }
+    }
+}
+
+/// A NEAR slashed validator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlashedValidator {
+    pub account_id: String,
+    pub is_double_sign: bool,
+}
+
+impl SlashedValidator {
+    fn from_raw(s: &'static AscRef<AscSlashedValidator>) -> Self {
+        Self {
+            account_id: s.account_id().to_string_lossy(),
+            is_double_sign: s.is_double_sign(),
+        }
+    }
+}
+
+/// A NEAR receipt along with its execution outcome, passed into receipt
+/// handlers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceiptWithOutcome {
+    pub outcome: ExecutionOutcome,
+    pub receipt: ActionReceipt,
+    pub block: Block,
+}
+
+/// A pointer to a NEAR receipt passed into a receipt handler.
+pub type ReceiptPtr = *const AscRef<AscReceiptWithOutcome>;
+
+impl ReceiptWithOutcome {
+    fn from_raw(r: &'static AscRef<AscReceiptWithOutcome>) -> Self {
+        Self {
+            outcome: ExecutionOutcome::from_raw(r.outcome()),
+            receipt: ActionReceipt::from_raw(r.receipt()),
+            block: Block::from_raw(r.block()),
+        }
+    }
+
+    /// Creates a receipt from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// This must be a pointer passed into a NEAR receipt handler.
+    pub unsafe fn from_ptr(ptr: ReceiptPtr) -> Self {
+        Self::from_raw(HostRef::new(ptr).into_ref())
+    }
+}
+
+/// A NEAR action receipt.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionReceipt {
+    pub predecessor_id: String,
+    pub receiver_id: String,
+    pub id: Hash,
+    pub signer_id: String,
+    pub signer_public_key: PublicKey,
+    pub gas_price: BigInt,
+    pub output_data_receivers: Vec<DataReceiver>,
+    pub input_data_ids: Vec<Hash>,
+    pub actions: Vec<Action>,
+}
+
+impl ActionReceipt {
+    fn from_raw(r: &'static AscRef<AscActionReceipt>) -> Self {
+        Self {
+            predecessor_id: r.predecessor_id().to_string_lossy(),
+            receiver_id: r.receiver_id().to_string_lossy(),
+            id: Hash::from_raw(r.id()),
+            signer_id: r.signer_id().to_string_lossy(),
+            signer_public_key: PublicKey::from_raw(r.signer_public_key()),
+            gas_price: BigInt::from_raw(r.gas_price()),
+            output_data_receivers: r
+                .output_data_receivers()
+                .as_slice()
+                .iter()
+                .map(|d| DataReceiver::from_raw(d.as_asc_ref()))
+                .collect(),
+            input_data_ids: r
+                .input_data_ids()
+                .as_slice()
+                .iter()
+                .map(|h| Hash::from_raw(h.as_asc_ref()))
+                .collect(),
+            actions: r
+                .actions()
+                .as_slice()
+                .iter()
+                .map(|a| Action::from_raw(a.as_asc_ref()))
+                .collect(),
+        }
+    }
+}
+
+/// A NEAR data receiver.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataReceiver {
+    pub data_id: Hash,
+    pub receiver_id: String,
+}
+
+impl DataReceiver {
+    fn from_raw(d: &'static AscRef<AscDataReceiver>) -> Self {
+        Self {
+            data_id: Hash::from_raw(d.data_id()),
+            receiver_id: d.receiver_id().to_string_lossy(),
+        }
+    }
+}
+
+/// A NEAR receipt action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    CreateAccount,
+    DeployContract {
+        code: Vec<u8>,
+    },
+    FunctionCall {
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: BigInt,
+    },
+    Transfer {
+        deposit: BigInt,
+    },
+    Stake {
+        stake: BigInt,
+        public_key: PublicKey,
+    },
+    AddKey {
+        public_key: PublicKey,
+        access_key: AccessKey,
+    },
+    DeleteKey {
+        public_key: PublicKey,
+    },
+    DeleteAccount {
+        beneficiary_id: String,
+    },
+}
+
+impl Action {
+    fn from_raw(a: &'static AscRef<AscAction>) -> Self {
+        match a.data() {
+            AscActionData::CreateAccount(_) => Self::CreateAccount,
+            AscActionData::DeployContract(a) => Self::DeployContract {
+                code: a.code().as_slice().to_owned(),
+            },
+            AscActionData::FunctionCall(a) => Self::FunctionCall {
+                method_name: a.method_name().to_string_lossy(),
+                args: a.args().as_slice().to_owned(),
+                gas: a.gas(),
+                deposit: BigInt::from_raw(a.deposit()),
+            },
+            AscActionData::Transfer(a) => Self::Transfer {
+                deposit: BigInt::from_raw(a.deposit()),
+            },
+            AscActionData::Stake(a) => Self::Stake {
+                stake: BigInt::from_raw(a.stake()),
+                public_key: PublicKey::from_raw(a.public_key()),
+            },
+            AscActionData::AddKey(a) => Self::AddKey {
+                public_key: PublicKey::from_raw(a.public_key()),
+                access_key: AccessKey::from_raw(a.access_key()),
+            },
+            AscActionData::DeleteKey(a) => Self::DeleteKey {
+                public_key: PublicKey::from_raw(a.public_key()),
+            },
+            AscActionData::DeleteAccount(a) => Self::DeleteAccount {
+                beneficiary_id: a.beneficiary_id().to_string_lossy(),
+            },
+            AscActionData::Unknown(kind) => panic!("unknown NEAR action kind {kind:#x}"),
+        }
+    }
+}
+
+/// A NEAR access key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccessKey {
+    pub nonce: u64,
+    pub permission: AccessKeyPermission,
+}
+
+impl AccessKey {
+    fn from_raw(k: &'static AscRef<AscAccessKey>) -> Self {
+        Self {
+            nonce: k.nonce(),
+            permission: AccessKeyPermission::from_raw(k.permission()),
+        }
+    }
+}
+
+/// A NEAR access key permission.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessKeyPermission {
+    FunctionCall {
+        allowance: Option<BigInt>,
+        receiver_id: String,
+        method_names: Vec<String>,
+    },
+    FullAccess,
+}
+
+impl AccessKeyPermission {
+    fn from_raw(p: &'static AscRef<AscAccessKeyPermission>) -> Self {
+        match p.data() {
+            AscAccessKeyPermissionData::FunctionCall(p) => Self::FunctionCall {
+                allowance: p.allowance().map(BigInt::from_raw),
+                receiver_id: p.receiver_id().to_string_lossy(),
+                method_names: p
+                    .method_names()
+                    .as_slice()
+                    .iter()
+                    .map(|m| m.as_asc_str().to_string_lossy())
+                    .collect(),
+            },
+            AscAccessKeyPermissionData::FullAccess(_) => Self::FullAccess,
+            AscAccessKeyPermissionData::Unknown(kind) => {
+                panic!("unknown NEAR access key permission kind {kind:#x}")
+            }
+        }
+    }
+}
+
+/// A NEAR receipt execution outcome.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExecutionOutcome {
+    pub gas_burnt: u64,
+    pub proof: Vec<MerklePathItem>,
+    pub block_hash: Hash,
+    pub id: Hash,
+    pub logs: Vec<String>,
+    pub receipt_ids: Vec<Hash>,
+    pub tokens_burnt: BigInt,
+    pub executor_id: String,
+    pub status: SuccessStatus,
+}
+
+impl ExecutionOutcome {
+    fn from_raw(o: &'static AscRef<AscExecutionOutcome>) -> Self {
+        Self {
+            gas_burnt: o.gas_burnt(),
+            proof: o
+                .proof()
+                .as_slice()
+                .iter()
+                .map(|p| MerklePathItem::from_raw(p.as_asc_ref()))
+                .collect(),
+            block_hash: Hash::from_raw(o.block_hash()),
+            id: Hash::from_raw(o.id()),
+            logs: o
+                .logs()
+                .as_slice()
+                .iter()
+                .map(|l| l.as_asc_str())
+                .map(AscStr::to_string_lossy)
+                .collect(),
+            receipt_ids: o
+                .receipt_ids()
+                .as_slice()
+                .iter()
+                .map(|h| Hash::from_raw(h.as_asc_ref()))
+                .collect(),
+            tokens_burnt: BigInt::from_raw(o.tokens_burnt()),
+            executor_id: o.executor_id().to_string_lossy(),
+            status: SuccessStatus::from_raw(o.status()),
+        }
+    }
+}
+
+/// A NEAR successful execution status.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SuccessStatus {
+    /// The execution returned a value.
+    Value(Vec<u8>),
+    /// The execution resulted in a new receipt.
+    ReceiptId(Hash),
+}
+
+impl SuccessStatus {
+    fn from_raw(s: &'static AscRef<AscSuccessStatus>) -> Self {
+        match s.data() {
+            AscSuccessStatusData::Value(v) => Self::Value(v.as_slice().to_owned()),
+            AscSuccessStatusData::ReceiptId(h) => Self::ReceiptId(Hash::from_raw(h)),
+            AscSuccessStatusData::Unknown(kind) => {
+                panic!("unknown NEAR success status kind {kind:#x}")
+            }
+        }
+    }
+}
+
+/// A NEAR Merkle proof path item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerklePathItem {
+    pub hash: Hash,
+    pub direction: Direction,
+}
+
+impl MerklePathItem {
+    fn from_raw(p: &'static AscRef<AscMerklePathItem>) -> Self {
+        Self {
+            hash: Hash::from_raw(p.hash()),
+            direction: match p.direction() {
+                0 => Direction::Left,
+                _ => Direction::Right,
+            },
+        }
+    }
+}
+
+/// The direction of a Merkle proof path item.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+}
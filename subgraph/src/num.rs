@@ -1,5 +1,8 @@
+#[cfg(feature = "serde")]
+pub(crate) mod serde;
+
 use crate::ffi::{
-    boxed::{AscCow, AscRef},
+    boxed::{AscCow, AscRef, HostRef},
     num::{AscBigDecimal, AscBigInt},
     str::AscString,
     sys,
@@ -7,6 +10,7 @@ use crate::ffi::{
 use std::{
     cmp::Ordering,
     convert::Infallible,
+    error::Error,
     fmt::{self, Debug, Display, Formatter, LowerHex, UpperHex},
     str::FromStr,
 };
@@ -42,6 +46,11 @@ impl BigInt {
         }
     }
 
+    /// Returns the signed little-endian representation of the integer.
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        self.inner.as_slice().to_owned()
+    }
+
     /// Returns the sign of the integer.
     pub fn signum(&self) -> i32 {
         signum_le(self.inner.as_slice())
@@ -50,10 +59,58 @@ impl BigInt {
     /// Parses a big integer from a string.
     pub fn parse(s: impl AsRef<str>) -> Self {
         let s = AscString::new(s.as_ref());
-        let result = unsafe { &*sys::big_int__from_string(s.as_ptr()) };
+        let result = unsafe { HostRef::new(sys::big_int__from_string(s.as_ptr())).into_ref() };
         Self::from_raw(result)
     }
 
+    /// Parses a decimal integer string without calling into the host, returning
+    /// `None` if the string is not a valid decimal integer.
+    #[cfg(any(feature = "native-json", feature = "serde"))]
+    pub(crate) fn parse_decimal(s: &str) -> Option<Self> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        // Accumulate the little-endian magnitude, with an additional `0` byte
+        // so that the most significant bit is never set.
+        let mut bytes = Vec::new();
+        for digit in digits.bytes() {
+            let mut carry = u32::from(digit - b'0');
+            for byte in &mut bytes {
+                let value = u32::from(*byte) * 10 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            while carry > 0 {
+                bytes.push(carry as u8);
+                carry >>= 8;
+            }
+        }
+        bytes.push(0);
+
+        if negative {
+            let mut carry = true;
+            for byte in &mut bytes {
+                (*byte, carry) = (!*byte).overflowing_add(carry as u8);
+            }
+        }
+
+        // Trim redundant sign extension bytes.
+        while let [.., prev, last] = bytes[..] {
+            if (last == 0 && prev < 0x80) || (last == 0xff && prev >= 0x80) {
+                bytes.pop();
+            } else {
+                break;
+            }
+        }
+
+        Some(Self::from_signed_bytes_le(bytes))
+    }
+
     /// Returns the sum of two big integers.
     pub fn plus(&self, rhs: &Self) -> Self {
         self.op(rhs, sys::big_int__plus)
@@ -77,7 +134,11 @@ impl BigInt {
     /// Returns the division of a big integer by a big decimal.
     pub fn divided_by_decimal(&self, rhs: &BigDecimal) -> BigDecimal {
         BigDecimal::from_raw(unsafe {
-            &*sys::big_int__divided_by_decimal(self.as_raw().as_ptr(), rhs.as_raw().as_ptr())
+            HostRef::new(sys::big_int__divided_by_decimal(
+                self.as_raw().as_ptr(),
+                rhs.as_raw().as_ptr(),
+            ))
+            .into_ref()
         })
     }
 
@@ -88,7 +149,9 @@ impl BigInt {
 
     /// function pow(x: BigInt, exp: u8): BigInt
     pub fn pow(&self, exp: u8) -> Self {
-        Self::from_raw(unsafe { &*sys::big_int__pow(self.as_raw().as_ptr(), exp) })
+        Self::from_raw(unsafe {
+            HostRef::new(sys::big_int__pow(self.as_raw().as_ptr(), exp)).into_ref()
+        })
     }
 
     /// Returns the bit-wise or of two big integers.
@@ -103,12 +166,16 @@ impl BigInt {
 
     /// Returns the left shift by `rhs` bits.
     pub fn left_shift(&self, rhs: u8) -> Self {
-        Self::from_raw(unsafe { &*sys::big_int__left_shift(self.as_raw().as_ptr(), rhs) })
+        Self::from_raw(unsafe {
+            HostRef::new(sys::big_int__left_shift(self.as_raw().as_ptr(), rhs)).into_ref()
+        })
     }
 
     /// Returns the arithmetic right shift by `rhs` bits.
     pub fn right_shift(&self, rhs: u8) -> Self {
-        Self::from_raw(unsafe { &*sys::big_int__right_shift(self.as_raw().as_ptr(), rhs) })
+        Self::from_raw(unsafe {
+            HostRef::new(sys::big_int__right_shift(self.as_raw().as_ptr(), rhs)).into_ref()
+        })
     }
 
     fn op(
@@ -119,7 +186,9 @@ impl BigInt {
             *const AscRef<AscBigInt>,
         ) -> *const AscRef<AscBigInt>,
     ) -> Self {
-        Self::from_raw(unsafe { &*op(self.as_raw().as_ptr(), other.as_raw().as_ptr()) })
+        Self::from_raw(unsafe {
+            HostRef::new(op(self.as_raw().as_ptr(), other.as_raw().as_ptr())).into_ref()
+        })
     }
 }
 
@@ -160,7 +229,9 @@ impl Default for BigInt {
 
 impl Display for BigInt {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let str = unsafe { &*sys::type_conversion__big_int_to_string(self.inner.as_ptr()) };
+        let str = unsafe {
+            HostRef::new(sys::type_conversion__big_int_to_string(self.inner.as_ptr())).into_ref()
+        };
 
         let str = str.to_string_lossy();
         let (is_non_negative, abs) = match str.strip_prefix('-') {
@@ -174,6 +245,28 @@ impl Display for BigInt {
 
 impl Eq for BigInt {}
 
+macro_rules! impl_from_int_for_big_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                Self::new(value.into())
+            }
+        }
+    )*};
+}
+
+impl_from_int_for_big_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl From<u128> for BigInt {
+    fn from(value: u128) -> Self {
+        // NOTE: Append a `0` byte, so that values with the most significant bit
+        // set are not interpreted as negative numbers.
+        let mut bytes = [0; 17];
+        bytes[..16].copy_from_slice(&value.to_le_bytes());
+        Self::from_signed_bytes_le(bytes)
+    }
+}
+
 impl FromStr for BigInt {
     type Err = Infallible;
 
@@ -212,8 +305,87 @@ impl UpperHex for BigInt {
     }
 }
 
+/// Converts signed little-endian bytes to a 128-bit signed integer, returning
+/// `None` if the value does not fit.
+fn i128_from_signed_bytes_le(bytes: &[u8]) -> Option<i128> {
+    let is_negative = signum_le(bytes) < 0;
+    let extension = if is_negative { 0xff } else { 0x00 };
+
+    let (low, high) = bytes.split_at(bytes.len().min(16));
+    if high.iter().any(|&b| b != extension) {
+        return None;
+    }
+
+    let mut buffer = [extension; 16];
+    buffer[..low.len()].copy_from_slice(low);
+    let value = i128::from_le_bytes(buffer);
+
+    // NOTE: Make sure that truncating the sign extension bytes did not change
+    // the sign of the value.
+    (value.is_negative() == is_negative).then_some(value)
+}
+
+/// Converts signed little-endian bytes to a 128-bit un-signed integer,
+/// returning `None` if the value does not fit.
+fn u128_from_signed_bytes_le(bytes: &[u8]) -> Option<u128> {
+    if signum_le(bytes) < 0 {
+        return None;
+    }
+
+    let (low, high) = bytes.split_at(bytes.len().min(16));
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+
+    let mut buffer = [0; 16];
+    buffer[..low.len()].copy_from_slice(low);
+    Some(u128::from_le_bytes(buffer))
+}
+
+macro_rules! impl_try_from_big_int {
+    ($($via:ident => $($t:ty),*;)*) => {$($(
+        impl TryFrom<&BigInt> for $t {
+            type Error = TryFromBigIntError;
+
+            fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+                $via(value.inner.as_slice())
+                    .and_then(|value| value.try_into().ok())
+                    .ok_or(TryFromBigIntError)
+            }
+        }
+
+        impl TryFrom<BigInt> for $t {
+            type Error = TryFromBigIntError;
+
+            fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+                Self::try_from(&value)
+            }
+        }
+    )*)*};
+}
+
+impl_try_from_big_int! {
+    i128_from_signed_bytes_le => i8, i16, i32, i64, i128, isize;
+    u128_from_signed_bytes_le => u8, u16, u32, u64, u128, usize;
+}
+
+/// An error converting a [`BigInt`] into a primitive integer type that is too
+/// small to represent it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TryFromBigIntError;
+
+impl Display for TryFromBigIntError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("big integer out of range for integral type")
+    }
+}
+
+impl Error for TryFromBigIntError {}
+
 fn fmt_hex(value: &BigInt, f: &mut Formatter, transform: impl FnOnce(&mut str)) -> fmt::Result {
-    let str = unsafe { &*sys::type_conversion__big_int_to_hex(&*value.inner as _) };
+    let str = unsafe {
+        HostRef::new(sys::type_conversion__big_int_to_hex(&*value.inner as _)).into_ref()
+    };
 
     let mut str = str.to_string_lossy();
     let str = match str.starts_with("0x") {
@@ -261,10 +433,29 @@ impl BigDecimal {
         }
     }
 
+    /// Creates a new decimal value from its digits and exponent, such that the
+    /// value is `digits * 10^exp`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_parts(digits: BigInt, exp: BigInt) -> Self {
+        let value = AscBigDecimal::new(digits.inner.into_owned(), exp.inner.into_owned());
+        Self {
+            inner: value.owned(),
+        }
+    }
+
+    /// Returns the digits and exponent of the decimal value.
+    #[cfg(feature = "serde")]
+    pub(crate) fn to_parts(&self) -> (BigInt, BigInt) {
+        (
+            BigInt::from_signed_bytes_le(self.inner.digits().as_slice()),
+            BigInt::from_signed_bytes_le(self.inner.exp().as_slice()),
+        )
+    }
+
     /// Parses a big decimal from a string.
     pub fn parse(s: impl AsRef<str>) -> Self {
         let s = AscString::new(s.as_ref());
-        let result = unsafe { &*sys::big_decimal__from_string(s.as_ptr()) };
+        let result = unsafe { HostRef::new(sys::big_decimal__from_string(s.as_ptr())).into_ref() };
         Self::from_raw(result)
     }
 
@@ -296,7 +487,9 @@ impl BigDecimal {
             *const AscRef<AscBigDecimal>,
         ) -> *const AscRef<AscBigDecimal>,
     ) -> Self {
-        Self::from_raw(unsafe { &*op(self.as_raw().as_ptr(), other.as_raw().as_ptr()) })
+        Self::from_raw(unsafe {
+            HostRef::new(op(self.as_raw().as_ptr(), other.as_raw().as_ptr())).into_ref()
+        })
     }
 }
 
@@ -314,7 +507,8 @@ impl Default for BigDecimal {
 
 impl Display for BigDecimal {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let str = unsafe { &*sys::big_decimal__to_string(self.inner.as_ptr()) };
+        let str =
+            unsafe { HostRef::new(sys::big_decimal__to_string(self.inner.as_ptr())).into_ref() };
 
         let str = str.to_string_lossy();
         let (is_non_negative, abs) = match str.strip_prefix('-') {
@@ -354,3 +548,78 @@ impl PartialOrd for BigDecimal {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signum_of_signed_bytes() {
+        assert_eq!(signum_le(&[]), 0);
+        assert_eq!(signum_le(&[0, 0]), 0);
+        assert_eq!(signum_le(&[1, 0]), 1);
+        assert_eq!(signum_le(&[0, 0x80]), -1);
+        assert_eq!(signum_le(&[0xff]), -1);
+    }
+
+    #[test]
+    fn i128_from_signed_bytes() {
+        assert_eq!(i128_from_signed_bytes_le(&[]), Some(0));
+        assert_eq!(i128_from_signed_bytes_le(&[0x7f]), Some(127));
+        assert_eq!(i128_from_signed_bytes_le(&[0x80]), Some(-128));
+        assert_eq!(i128_from_signed_bytes_le(&[0xff]), Some(-1));
+        assert_eq!(i128_from_signed_bytes_le(&[0x80, 0x00]), Some(128));
+        assert_eq!(i128_from_signed_bytes_le(&[0xff; 32]), Some(-1));
+        assert_eq!(i128_from_signed_bytes_le(&[0x00; 32]), Some(0));
+
+        for value in [i128::MIN, i128::MAX, -1, 0, 1, 0x1234] {
+            let mut bytes = value.to_le_bytes().to_vec();
+            assert_eq!(i128_from_signed_bytes_le(&bytes), Some(value));
+
+            // Redundant sign extension bytes are allowed.
+            let extension = if value < 0 { 0xff } else { 0x00 };
+            bytes.extend([extension; 4]);
+            assert_eq!(i128_from_signed_bytes_le(&bytes), Some(value));
+        }
+    }
+
+    #[test]
+    fn i128_from_signed_bytes_out_of_range() {
+        // 2^127 needs a 17th byte to be positive.
+        let mut bytes = [0x00; 17];
+        bytes[15] = 0x80;
+        assert_eq!(i128_from_signed_bytes_le(&bytes), None);
+
+        // -2^127 - 1 needs a 17th byte to be negative.
+        let mut bytes = [0xff; 17];
+        bytes[15] = 0x7f;
+        assert_eq!(i128_from_signed_bytes_le(&bytes), None);
+
+        // Non sign extension bytes past the 16th byte.
+        let mut bytes = [0x00; 20];
+        bytes[18] = 0x01;
+        assert_eq!(i128_from_signed_bytes_le(&bytes), None);
+    }
+
+    #[test]
+    fn u128_from_signed_bytes() {
+        assert_eq!(u128_from_signed_bytes_le(&[]), Some(0));
+        assert_eq!(u128_from_signed_bytes_le(&[0x7f]), Some(127));
+        assert_eq!(u128_from_signed_bytes_le(&[0xff, 0x00]), Some(255));
+        assert_eq!(u128_from_signed_bytes_le(&[0x00; 32]), Some(0));
+
+        let mut bytes = [0xff; 17];
+        bytes[16] = 0x00;
+        assert_eq!(u128_from_signed_bytes_le(&bytes), Some(u128::MAX));
+    }
+
+    #[test]
+    fn u128_from_signed_bytes_out_of_range() {
+        assert_eq!(u128_from_signed_bytes_le(&[0xff]), None);
+        assert_eq!(u128_from_signed_bytes_le(&[0x00, 0x80]), None);
+
+        let mut bytes = [0x00; 18];
+        bytes[16] = 0x01;
+        assert_eq!(u128_from_signed_bytes_le(&bytes), None);
+    }
+}
@@ -0,0 +1,212 @@
+//! Serde support for big numbers.
+//!
+//! Big numbers are serialized as newtype structs with special names, allowing
+//! Subgraph specific serializers (such as the entity serializer) to recognize
+//! them and convert them to the corresponding native values. Other formats
+//! see decimal strings for human readable formats. In binary formats, big
+//! integers are serialized as signed little-endian bytes, and big decimals as
+//! a tuple of big integer digits and exponent.
+
+use super::{BigDecimal, BigInt};
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Unexpected, Visitor},
+    ser::{Serialize, Serializer},
+};
+use std::fmt::{self, Formatter};
+
+/// The newtype struct name used for serializing big integers.
+pub(crate) const BIG_INT_TOKEN: &str = "$subgraph::BigInt";
+
+/// The newtype struct name used for serializing big decimals.
+pub(crate) const BIG_DECIMAL_TOKEN: &str = "$subgraph::BigDecimal";
+
+impl Serialize for BigInt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_newtype_struct(BIG_INT_TOKEN, &self.to_string())
+        } else {
+            serializer.serialize_newtype_struct(BIG_INT_TOKEN, &Bytes(self.inner.as_slice()))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BigInt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(BIG_INT_TOKEN, BigIntVisitor)
+    }
+}
+
+struct BigIntVisitor;
+
+impl<'de> Visitor<'de> for BigIntVisitor {
+    type Value = BigInt;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a big integer")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        BigInt::parse_decimal(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(BigInt::from_signed_bytes_le(v))
+    }
+}
+
+impl Serialize for BigDecimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_newtype_struct(BIG_DECIMAL_TOKEN, &self.to_string())
+        } else {
+            serializer.serialize_newtype_struct(BIG_DECIMAL_TOKEN, &self.to_parts())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BigDecimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(BIG_DECIMAL_TOKEN, BigDecimalVisitor)
+    }
+}
+
+struct BigDecimalVisitor;
+
+impl<'de> Visitor<'de> for BigDecimalVisitor {
+    type Value = BigDecimal;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a big decimal")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(BigDecimal::new(v.into()))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(BigDecimal::new(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(BigDecimal::new(v.into()))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(BigDecimal::from_big_int(v.into()))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let digits = seq
+            .next_element::<BigInt>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let exp = seq
+            .next_element::<BigInt>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(BigDecimal::from_parts(digits, exp))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !v.is_finite() {
+            return Err(E::invalid_value(Unexpected::Float(v), &self));
+        }
+        Ok(BigDecimal::parse(v.to_string()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !is_decimal(v) {
+            return Err(E::invalid_value(Unexpected::Str(v), &self));
+        }
+        Ok(BigDecimal::parse(v))
+    }
+}
+
+/// Helper for serializing a slice as bytes.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Returns `true` if the string is a valid decimal number, with an optional
+/// fractional part and exponent.
+///
+/// The host aborts when parsing invalid numbers, so strings are checked before
+/// being passed to it.
+fn is_decimal(s: &str) -> bool {
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let mantissa = mantissa.strip_prefix('-').unwrap_or(mantissa);
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+    !(int.is_empty() && frac.is_empty())
+        && digits(int)
+        && digits(frac)
+        && exponent
+            .map(|e| {
+                let e = e.strip_prefix(['+', '-']).unwrap_or(e);
+                !e.is_empty() && digits(e)
+            })
+            .unwrap_or(true)
+}
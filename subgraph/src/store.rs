@@ -1,35 +1,75 @@
 //! Entity storage.
 
 use crate::{
-    entity::{Entity, EntityExt as _},
-    ffi::{str::AscString, sys},
+    entity::{Entity, EntityId},
+    ffi::{boxed::HostRef, str::AscString, sys},
 };
 
 /// Gets an entity by name and ID.
-pub fn get(entity: impl AsRef<str>, id: impl AsRef<str>) -> Option<Entity> {
-    let entity = AscString::new(entity.as_ref());
-    let id = AscString::new(id.as_ref());
+pub fn get(entity: impl AsRef<str>, id: impl EntityId) -> Option<Entity> {
+    let name = entity.as_ref();
+    let entity = AscString::new(name);
+    let id = AscString::new(&id.to_entity_id());
+    let data =
+        unsafe { HostRef::new_nullable(sys::store__get(entity.as_ptr(), id.as_ptr()))?.into_ref() };
+    Some(Entity::from_raw(data).with_name(name))
+}
+
+/// Gets an entity by name and ID, only considering entities that were created
+/// or updated in the current block.
+///
+/// This is cheaper than [`get`] as it never queries the database, and should
+/// be preferred for entities known to have been written in the current block.
+pub fn get_in_block(entity: impl AsRef<str>, id: impl EntityId) -> Option<Entity> {
+    let name = entity.as_ref();
+    let entity = AscString::new(name);
+    let id = AscString::new(&id.to_entity_id());
     let data = unsafe {
-        let data = sys::store__get(entity.as_ptr(), id.as_ptr());
-        if data.is_null() {
-            return None;
-        }
-        &*data
+        HostRef::new_nullable(sys::store__get_in_block(entity.as_ptr(), id.as_ptr()))?.into_ref()
+    };
+    Some(Entity::from_raw(data).with_name(name))
+}
+
+/// Loads the entities related to an entity through a derived field.
+///
+/// The field must be a `@derivedFrom` field of the entity type, for example
+/// the list of `Transfer`s of a `Holder`. The host does not report the type of
+/// the related entities, so they can be tagged with [`Entity::with_name`] for
+/// more useful field access errors.
+pub fn load_related(
+    entity: impl AsRef<str>,
+    id: impl EntityId,
+    field: impl AsRef<str>,
+) -> Vec<Entity> {
+    let entity = AscString::new(entity.as_ref());
+    let id = AscString::new(&id.to_entity_id());
+    let field = AscString::new(field.as_ref());
+    let related = unsafe {
+        HostRef::new(sys::store__load_related(
+            entity.as_ptr(),
+            id.as_ptr(),
+            field.as_ptr(),
+        ))
+        .into_ref()
     };
-    Some(Entity::from_raw(data))
+    related
+        .as_slice()
+        .iter()
+        .map(|data| Entity::from_raw(data.as_asc_ref()))
+        .collect()
 }
 
 /// Sets an entity value by name and ID.
-pub fn set(entity: impl AsRef<str>, id: impl AsRef<str>, data: &Entity) {
+pub fn set(entity: impl AsRef<str>, id: impl EntityId, data: &Entity) {
     let entity = AscString::new(entity.as_ref());
-    let id = AscString::new(id.as_ref());
+    let id = AscString::new(&id.to_entity_id());
     let data = data.to_raw();
     unsafe { sys::store__set(entity.as_ptr(), id.as_ptr(), data.as_ptr()) };
 }
 
 /// Removes an entity by name and ID.
-pub fn remove(entity: impl AsRef<str>, id: impl AsRef<str>) {
+pub fn remove(entity: impl AsRef<str>, id: impl EntityId) {
     let entity = AscString::new(entity.as_ref());
-    let id = AscString::new(id.as_ref());
+    let id = AscString::new(&id.to_entity_id());
     unsafe { sys::store__remove(entity.as_ptr(), id.as_ptr()) };
 }
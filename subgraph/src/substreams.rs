@@ -0,0 +1,42 @@
+//! Substreams-powered Subgraph triggers.
+//!
+//! Substreams handlers are passed the raw protobuf-encoded output of the
+//! Substreams module that the Subgraph consumes. With the `prost` feature,
+//! this output can be decoded directly into generated message types, and the
+//! common `EntityChanges` output can be applied to the store.
+
+#[cfg(feature = "prost")]
+mod changes;
+
+#[cfg(feature = "prost")]
+pub use self::changes::{Array, EntityChange, EntityChanges, Field, Operation, Typed, Value};
+use crate::ffi::{
+    boxed::{AscRef, HostRef},
+    types::AscBytes,
+};
+
+/// A pointer to Substreams module output passed into a handler.
+pub type DataPtr = *const AscRef<AscBytes>;
+
+/// Returns the raw Substreams module output from a handler pointer.
+///
+/// # Safety
+///
+/// This must be a pointer passed into a Substreams handler.
+pub unsafe fn from_ptr(ptr: DataPtr) -> Vec<u8> {
+    HostRef::new(ptr).into_ref().as_slice().to_owned()
+}
+
+/// Decodes Substreams module output from a handler pointer into a protobuf
+/// message.
+///
+/// # Safety
+///
+/// This must be a pointer passed into a Substreams handler.
+#[cfg(feature = "prost")]
+pub unsafe fn decode_ptr<T>(ptr: DataPtr) -> Result<T, prost::DecodeError>
+where
+    T: prost::Message + Default,
+{
+    T::decode(HostRef::new(ptr).into_ref().as_slice())
+}
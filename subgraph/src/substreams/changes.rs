@@ -0,0 +1,255 @@
+//! Substreams entity changes.
+//!
+//! These are the `sf.substreams.sink.entity.v1` messages produced by Substreams
+//! modules that output entity changes directly.
+
+use crate::{
+    entity::{self, Entity},
+    num::{BigDecimal, BigInt},
+    store,
+};
+use prost::{Enumeration, Message, Oneof};
+
+/// A batch of entity changes.
+#[derive(Clone, PartialEq, Message)]
+pub struct EntityChanges {
+    #[prost(message, repeated, tag = "5")]
+    pub entity_changes: Vec<EntityChange>,
+}
+
+impl EntityChanges {
+    /// Applies all entity changes to the store, in order.
+    pub fn apply(&self) {
+        for change in &self.entity_changes {
+            change.apply();
+        }
+    }
+}
+
+/// A change to a single entity.
+#[derive(Clone, PartialEq, Message)]
+pub struct EntityChange {
+    #[prost(string, tag = "1")]
+    pub entity: String,
+    #[prost(string, tag = "2")]
+    pub id: String,
+    #[prost(uint64, tag = "3")]
+    pub ordinal: u64,
+    #[prost(enumeration = "Operation", tag = "4")]
+    pub operation: i32,
+    #[prost(message, repeated, tag = "5")]
+    pub fields: Vec<Field>,
+}
+
+impl EntityChange {
+    /// Applies the change to the store.
+    ///
+    /// Created entities are stored with the new values of the changed fields,
+    /// while updated entities have them merged into the existing entity.
+    /// Changes with unset or final operations are ignored.
+    pub fn apply(&self) {
+        match self.operation() {
+            Operation::Create => {
+                let mut data = Entity::new();
+                self.merge_into(&mut data);
+                store::set(&self.entity, &self.id, &data);
+            }
+            Operation::Update => {
+                let mut data = store::get(&self.entity, &self.id).unwrap_or_default();
+                self.merge_into(&mut data);
+                store::set(&self.entity, &self.id, &data);
+            }
+            Operation::Delete => store::remove(&self.entity, &self.id),
+            Operation::Unset | Operation::Final => {}
+        }
+    }
+
+    /// Sets the new values of the changed fields on an entity.
+    pub fn merge_into(&self, entity: &mut Entity) {
+        for field in &self.fields {
+            let value = field.new_value.clone().map(entity::Value::from);
+            entity.set(&field.name, value.unwrap_or_default());
+        }
+    }
+}
+
+/// An entity change operation.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Enumeration)]
+#[repr(i32)]
+pub enum Operation {
+    Unset = 0,
+    Create = 1,
+    Update = 2,
+    Delete = 3,
+    Final = 4,
+}
+
+/// A changed entity field.
+#[derive(Clone, PartialEq, Message)]
+pub struct Field {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(message, optional, tag = "3")]
+    pub new_value: Option<Value>,
+    #[prost(message, optional, tag = "5")]
+    pub old_value: Option<Value>,
+}
+
+/// An entity field value.
+#[derive(Clone, PartialEq, Message)]
+pub struct Value {
+    #[prost(oneof = "Typed", tags = "1, 2, 3, 4, 5, 6, 7, 10")]
+    pub typed: Option<Typed>,
+}
+
+/// The typed data of an entity field value.
+#[derive(Clone, PartialEq, Oneof)]
+pub enum Typed {
+    #[prost(int32, tag = "1")]
+    Int32(i32),
+    #[prost(string, tag = "2")]
+    Bigdecimal(String),
+    #[prost(string, tag = "3")]
+    Bigint(String),
+    #[prost(string, tag = "4")]
+    String(String),
+    #[prost(bytes, tag = "5")]
+    Bytes(Vec<u8>),
+    #[prost(bool, tag = "6")]
+    Bool(bool),
+    /// A timestamp in microseconds since the Unix epoch.
+    #[prost(int64, tag = "7")]
+    Timestamp(i64),
+    #[prost(message, tag = "10")]
+    Array(Array),
+}
+
+/// An array of entity field values.
+#[derive(Clone, PartialEq, Message)]
+pub struct Array {
+    #[prost(message, repeated, tag = "1")]
+    pub value: Vec<Value>,
+}
+
+impl From<Value> for entity::Value {
+    /// Converts a Substreams value into an entity value.
+    ///
+    /// Big number strings are parsed by the host, which traps on invalid
+    /// values.
+    fn from(value: Value) -> Self {
+        match value.typed {
+            Some(Typed::Int32(value)) => Self::Int(value),
+            Some(Typed::Bigdecimal(value)) => Self::BigDecimal(BigDecimal::parse(value)),
+            Some(Typed::Bigint(value)) => Self::BigInt(BigInt::parse(value)),
+            Some(Typed::String(value)) => Self::String(value),
+            Some(Typed::Bytes(value)) => Self::Bytes(value),
+            Some(Typed::Bool(value)) => Self::Bool(value),
+            Some(Typed::Timestamp(value)) => Self::Timestamp(value),
+            Some(Typed::Array(array)) => {
+                Self::Array(array.value.into_iter().map(Self::from).collect())
+            }
+            None => Self::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(typed: Typed) -> Value {
+        Value { typed: Some(typed) }
+    }
+
+    fn field(name: &str, new_value: Option<Value>) -> Field {
+        Field {
+            name: name.to_owned(),
+            new_value,
+            old_value: None,
+        }
+    }
+
+    #[test]
+    fn merge_update() {
+        let change = EntityChange {
+            entity: "Token".to_owned(),
+            id: "token".to_owned(),
+            ordinal: 0,
+            operation: Operation::Update as _,
+            fields: vec![
+                field("symbol", Some(value(Typed::String("GRT".to_owned())))),
+                field("decimals", Some(value(Typed::Int32(18)))),
+                field("owner", None),
+            ],
+        };
+
+        let mut entity = Entity::builder()
+            .field("id", "token")
+            .field("symbol", "OLD")
+            .field("owner", vec![1_u8; 20])
+            .field("paused", false)
+            .build();
+        change.merge_into(&mut entity);
+
+        assert_eq!(
+            entity.keys().collect::<Vec<_>>(),
+            ["id", "symbol", "owner", "paused", "decimals"],
+        );
+        assert!(matches!(&entity["id"], entity::Value::String(id) if id == "token"));
+        assert!(matches!(&entity["symbol"], entity::Value::String(s) if s == "GRT"));
+        assert!(matches!(entity["owner"], entity::Value::Null));
+        assert!(matches!(entity["paused"], entity::Value::Bool(false)));
+        assert!(matches!(entity["decimals"], entity::Value::Int(18)));
+    }
+
+    #[test]
+    fn value_conversions() {
+        let convert = |typed| entity::Value::from(value(typed));
+
+        assert!(matches!(convert(Typed::Int32(-1)), entity::Value::Int(-1)));
+        assert!(matches!(
+            convert(Typed::String("a".to_owned())),
+            entity::Value::String(s) if s == "a"
+        ));
+        assert!(matches!(
+            convert(Typed::Bytes(vec![1, 2])),
+            entity::Value::Bytes(b) if b == [1, 2]
+        ));
+        assert!(matches!(
+            convert(Typed::Bool(true)),
+            entity::Value::Bool(true)
+        ));
+        assert!(matches!(
+            convert(Typed::Timestamp(1_700_000_000_000_000)),
+            entity::Value::Timestamp(1_700_000_000_000_000)
+        ));
+        assert!(matches!(
+            convert(Typed::Array(Array {
+                value: vec![value(Typed::Int32(1)), Value { typed: None }],
+            })),
+            entity::Value::Array(a)
+                if matches!(&a[..], [entity::Value::Int(1), entity::Value::Null])
+        ));
+        assert!(matches!(
+            entity::Value::from(Value { typed: None }),
+            entity::Value::Null
+        ));
+    }
+
+    #[test]
+    fn decode_entity_changes() {
+        let changes = EntityChanges {
+            entity_changes: vec![EntityChange {
+                entity: "Token".to_owned(),
+                id: "token".to_owned(),
+                ordinal: 1,
+                operation: Operation::Delete as _,
+                fields: vec![field("symbol", Some(value(Typed::Bool(true))))],
+            }],
+        };
+
+        let decoded = EntityChanges::decode(changes.encode_to_vec().as_slice()).unwrap();
+        assert!(decoded == changes);
+        assert_eq!(decoded.entity_changes[0].operation(), Operation::Delete);
+    }
+}